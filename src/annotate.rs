@@ -0,0 +1,102 @@
+use anyhow::*;
+use async_recursion::*;
+
+use crate::language::*;
+use crate::sexpr::SExpr;
+
+// The binding powers under the symbols like the comments of pratt6
+//
+//   1   +   2   *   3
+// 0   50 51   80 81
+//
+// The left_bp is put just before its symbol and the right_bp just after
+// 0 after a symbol means the min_bp until the next symbol of a mixfix operator
+//
+//   1   *   (   2   +   3   )
+// 0   80 81  0    50 51
+//
+// The text is rebuilt from the tree, so blanks of the input are not kept
+pub async fn annotate(language: &Language, expr: &SExpr) -> Result<String> {
+    let mut tokens = Vec::new();
+    push_tokens(language, expr, &mut tokens).await?;
+
+    let mut text = String::new();
+    let mut bps = String::from("0");  // parse_expr starts with min_bp 0
+    let mut right_of_previous = String::new();
+
+    for (i, token) in tokens.iter().enumerate() {
+        let left = token.left_bp.map(|bp| bp.to_string()).unwrap_or_default();
+        let gap = if i == 0 {
+            2
+        } else {
+            3.max(right_of_previous.len() + 1 + left.len())
+        };
+
+        // Under the gap: the right_bp of the previous token, spaces, and the left_bp of this token
+        let column = text.chars().count() + gap;
+        bps.push_str(&right_of_previous);
+        let filled = bps.chars().count();
+        bps.push_str(&" ".repeat((column - left.len()).saturating_sub(filled)));
+        bps.push_str(&left);
+
+        text.push_str(&" ".repeat(gap));
+        text.push_str(&token.text);
+        bps.push_str(&" ".repeat(token.text.chars().count()));  // Under the token, which may be an empty atom
+
+        right_of_previous = token.right_bp.map(|bp| bp.to_string()).unwrap_or_default();
+    }
+    bps.push_str(&right_of_previous);
+
+    Ok(format!("{}\n{}", text, bps.trim_end()))
+}
+
+struct Token {
+    text: String,
+    left_bp: Option<i32>,
+    right_bp: Option<i32>,
+}
+
+#[async_recursion]
+async fn push_tokens(language: &Language, expr: &SExpr, tokens: &mut Vec<Token>) -> Result<()> {
+    let l = match expr {
+        SExpr::Atom(s) => {
+            tokens.push(Token{text: s.clone(), left_bp: None, right_bp: None});
+            return Ok(());
+        },
+        SExpr::List(l) => l,
+    };
+
+    let (name, children) = match l.split_first() {
+        Some((SExpr::Atom(name), children)) => (name, children),
+        _ => bail!("Expected an operator name at the head of {}", expr),
+    };
+
+    let operator = language.find_operator(name, children.len())
+            .with_context(|| format!("Unknown operator {} with {} children", name, children.len()))?;
+    let (left_bp, right_bp) = operator.binding_powers();
+    let symbols = operator.symbols();
+    let mut children = children.iter();
+
+    if let OperatorRef::Following(_) = operator {
+        push_tokens(language, children.next().unwrap(), tokens).await?;
+    }
+
+    for (i, symbol) in symbols.iter().enumerate() {
+        if i > 0 {
+            push_tokens(language, children.next().unwrap(), tokens).await?;
+        }
+
+        let is_last = i == symbols.len() - 1;
+        tokens.push(Token{
+            text: symbol.to_string(),
+            left_bp: if i == 0 { left_bp } else { None },
+            right_bp: if is_last { right_bp } else { Some(0) },
+        });
+    }
+
+    if let Some(trailing_expr) = children.next() {  // The operand of the right_bp
+        push_tokens(language, trailing_expr, tokens).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::error::ParseError;
+use crate::input::Input;
+use crate::language::*;
+use crate::sexpr::{to_sexpr, SExpr, Shape};
+use crate::shunting_yard::{shunting_yard, Operands};
+
+// An operator name or an atom text kept once in the Arena
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interned(u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Node {
+    Atom(Interned),
+    Operator{name: Interned, first_child: u32, arity: u32},  // The children are in Arena::children
+}
+
+// Trees of many expressions in a few Vecs
+//
+// (+ 1 (* 2 3)) is
+//   nodes     [1, 2, 3, (* #1 #2), (+ #0 #3)]
+//   children  [#1, #2, #0, #3]
+//   strings   ["1", "2", "3", "*", "+"]
+//
+// clear drops the trees but keeps the capacity and the interned strings,
+// so parsing the next expression allocates only for a string not seen before
+// The strings are never dropped by clear, so a service reading many distinct atoms
+// calls reset from time to time, like after every batch or when interned_len grows too large
+#[derive(Debug, Default)]
+pub struct Arena {
+    nodes: Vec<Node>,
+    children: Vec<NodeId>,
+    strings: Vec<String>,
+    interned: HashMap<String, Interned>,
+    operands: Vec<NodeId>,  // The operands of the shunting-yard
+}
+
+impl Arena {
+    pub async fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.children.clear();
+        self.operands.clear();
+    }
+
+    // clear and also forget the interned strings, whose Interned are no longer valid
+    pub fn reset(&mut self) {
+        self.clear();
+        self.strings.clear();
+        self.interned.clear();
+    }
+
+    // The number of the interned strings, which only reset makes smaller
+    pub fn interned_len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn intern(&mut self, s: &str) -> Interned {
+        if let Some(interned) = self.interned.get(s) {
+            return *interned;
+        }
+
+        let interned = Interned(self.strings.len() as u32);
+        self.strings.push(s.into());
+        self.interned.insert(s.into(), interned);
+        interned
+    }
+
+    pub fn resolve(&self, interned: Interned) -> &str {
+        &self.strings[interned.0 as usize]
+    }
+
+    pub fn node(&self, id: NodeId) -> Node {
+        self.nodes[id.0 as usize]
+    }
+
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        match self.node(id) {
+            Node::Atom(_) => &[],
+            Node::Operator{first_child, arity, ..} => {
+                &self.children[first_child as usize..(first_child + arity) as usize]
+            },
+        }
+    }
+
+    // The name of an operator or the text of an atom
+    pub fn text(&self, id: NodeId) -> &str {
+        match self.node(id) {
+            Node::Atom(text) => self.resolve(text),
+            Node::Operator{name, ..} => self.resolve(name),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    // The same SExpr as parse_expr gives, made without recursion
+    pub fn to_sexpr(&self, root: NodeId) -> SExpr {
+        to_sexpr(&root, |id| self.shape(*id))
+    }
+
+    fn shape(&self, id: NodeId) -> Shape<'_, NodeId> {
+        match self.node(id) {
+            Node::Atom(text) => Shape::Atom(self.resolve(text)),
+            Node::Operator{name, ..} => Shape::List(Some(self.resolve(name)), self.children(id)),
+        }
+    }
+
+    fn push(&mut self, node: Node) -> NodeId {
+        let id = NodeId(self.nodes.len() as u32);
+        self.nodes.push(node);
+        id
+    }
+}
+
+impl<'a> Operands<'a> for Arena {
+    fn atom(&mut self, text: &str, span: Range<usize>) {
+        let text = self.intern(&text[span]);
+        let id = self.push(Node::Atom(text));
+        self.operands.push(id);
+    }
+
+    fn operator(&mut self, operator: OperatorRef<'a>, arity: usize) {
+        let name = self.intern(operator.name());
+        let first_child = self.children.len() as u32;
+        let start = self.operands.len() - arity;
+        self.children.extend(self.operands.drain(start..));
+
+        let id = self.push(Node::Operator{name, first_child, arity: arity as u32});
+        self.operands.push(id);
+    }
+}
+
+// Parses into the arena with the shunting-yard, which gives the same tree as parse_expr
+//
+// let mut arena = Arena::new().await;
+// for line in lines {
+//     arena.clear();
+//     let root = parse_expr_in_arena(&language, &mut Input::borrowed(line).await, 0, &mut arena).await?;
+// }
+//
+// The atoms are interned, so the Input may be an Input::borrowed text as well as an owned one
+pub async fn parse_expr_in_arena<T: AsRef<str>>(
+    language: &Language,
+    input: &mut Input<T>,
+    min_bp: i32,
+    arena: &mut Arena,
+) -> Result<NodeId, ParseError> {
+    arena.operands.clear();
+    shunting_yard(language, input, min_bp, arena).await?;
+    Ok(arena.operands.pop().unwrap())
+}
@@ -0,0 +1,46 @@
+use anyhow::{bail, Error};
+
+use crate::error::ParseError;
+use crate::input::Input;
+use crate::iterative::parse_expr_iterative;
+use crate::language::Language;
+use crate::parser::{expect_end, parse_expr};
+use crate::sexpr::SExpr;
+use crate::shunting_yard::parse_expr_shunting_yard;
+
+// How the expression is parsed, which gives the same SExpr in any way
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Backend {
+    #[default]
+    Pratt,         // parse_expr, which recurses for each operand
+    ShuntingYard,  // parse_expr_shunting_yard, for long flat inputs
+    Iterative,     // parse_expr_iterative, the same loop as parse_expr for deep inputs
+}
+
+impl std::str::FromStr for Backend {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        match s {
+            "pratt" => Ok(Backend::Pratt),
+            "shunting-yard" => Ok(Backend::ShuntingYard),
+            "iterative" => Ok(Backend::Iterative),
+            _ => bail!("Unknown backend {}, use pratt, shunting-yard or iterative", s),
+        }
+    }
+}
+
+pub async fn parse_expr_with(language: &Language, input: &mut Input, min_bp: i32, backend: Backend) -> Result<SExpr, ParseError> {
+    match backend {
+        Backend::Pratt => parse_expr(language, input, min_bp).await,
+        Backend::ShuntingYard => parse_expr_shunting_yard(language, input, min_bp).await,
+        Backend::Iterative => parse_expr_iterative(language, input, min_bp).await,
+    }
+}
+
+// parse_all with the backend
+pub async fn parse_all_with(language: &Language, input: &mut Input, backend: Backend) -> Result<SExpr, ParseError> {
+    let expr = parse_expr_with(language, input, 0, backend).await?;
+    expect_end(input).await?;
+    Ok(expr)
+}
@@ -0,0 +1,96 @@
+use std::ops::Range;
+
+use crate::error::ParseError;
+use crate::input::Input;
+use crate::language::*;
+use crate::parser::expect_end;
+use crate::sexpr::{drop_children, to_sexpr, write_tree, SExpr, Shape};
+use crate::shunting_yard::{shunting_yard, Operands};
+
+// An SExpr which does not own the strings
+// An atom is a slice of the text of Input<&str> and an operator name is the name in the Language,
+// so parsing allocates only the Vecs of the lists
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BorrowedSExpr<'t> {
+    Atom(&'t str),
+    List(Vec<BorrowedSExpr<'t>>),
+}
+
+impl BorrowedSExpr<'_> {
+    // The same tree with owned atoms, made without recursion
+    pub fn to_sexpr(&self) -> SExpr {
+        to_sexpr(self, BorrowedSExpr::shape)
+    }
+
+    // For keeping the tree after the text is dropped
+    pub fn into_owned(self) -> SExpr {
+        self.to_sexpr()
+    }
+
+    fn shape(&self) -> Shape<'_, Self> {
+        match self {
+            BorrowedSExpr::Atom(s) => Shape::Atom(s),
+            BorrowedSExpr::List(l) => Shape::List(None, l),
+        }
+    }
+}
+
+impl Drop for BorrowedSExpr<'_> {
+    fn drop(&mut self) {
+        if let BorrowedSExpr::List(l) = self {
+            drop_children(std::mem::take(l), |expr| match expr {
+                BorrowedSExpr::List(l) => std::mem::take(l),
+                BorrowedSExpr::Atom(_) => Vec::new(),
+            });
+        }
+    }
+}
+
+// The same text as the Display of SExpr
+impl std::fmt::Display for BorrowedSExpr<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write_tree(f, self, BorrowedSExpr::shape)
+    }
+}
+
+// The operands of the shunting-yard, which slice the text it was given
+struct Builder<'t> {
+    text: &'t str,
+    operands: Vec<BorrowedSExpr<'t>>,
+}
+
+impl<'l: 't, 't> Operands<'l> for Builder<'t> {
+    fn atom(&mut self, _text: &str, span: Range<usize>) {
+        self.operands.push(BorrowedSExpr::Atom(&self.text[span]));
+    }
+
+    fn operator(&mut self, operator: OperatorRef<'l>, arity: usize) {
+        let mut children = vec![BorrowedSExpr::Atom(operator.name())];
+        children.extend(self.operands.drain(self.operands.len() - arity..));
+        self.operands.push(BorrowedSExpr::List(children));
+    }
+}
+
+// parse_expr giving a BorrowedSExpr
+// The tree borrows the text and the language but not the Input,
+// so the next expression can be parsed while the tree is kept
+//
+// let mut input = Input::borrowed("1+2 3*4").await;
+// let first = parse_expr_borrowed(&language, &mut input, 0).await?;
+// let second = parse_expr_borrowed(&language, &mut input, 0).await?;
+pub async fn parse_expr_borrowed<'l: 't, 't>(
+    language: &'l Language,
+    input: &mut Input<&'t str>,
+    min_bp: i32,
+) -> Result<BorrowedSExpr<'t>, ParseError> {
+    let mut builder = Builder{text: input.text, operands: Vec::new()};
+    shunting_yard(language, input, min_bp, &mut builder).await?;
+    Ok(builder.operands.pop().unwrap())
+}
+
+// parse_all giving a BorrowedSExpr
+pub async fn parse_all_borrowed<'l: 't, 't>(language: &'l Language, input: &mut Input<&'t str>) -> Result<BorrowedSExpr<'t>, ParseError> {
+    let expr = parse_expr_borrowed(language, input, 0).await?;
+    expect_end(input).await?;
+    Ok(expr)
+}
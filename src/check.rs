@@ -0,0 +1,79 @@
+use crate::input::is_blank;
+use crate::language::*;
+
+// Problems of a language table which parse_expr would not notice
+// An empty list means the language is fine
+pub async fn check_language(language: &Language) -> Vec<String> {
+    let mut problems = Vec::new();
+    let operators: Vec<OperatorRef> = language.leading_operators.iter().map(OperatorRef::Leading)
+            .chain(language.following_operators.iter().map(OperatorRef::Following))
+            .collect();
+
+    for operator in operators.iter() {
+        let name = operator.name();
+        let symbols = operator.symbols();
+
+        if symbols.is_empty() {
+            problems.push(format!("{} has no symbols", name));
+            continue;
+        }
+
+        for symbol in symbols.iter() {
+            if symbol.is_ascii_digit() || symbol.is_ascii_lowercase() {
+                problems.push(format!("{} uses {} which is read as an atom", name, symbol));
+            }
+            if *symbol == '.' {  // 1.2 is one atom
+                problems.push(format!("{} uses . which is read as a part of a number", name));
+            }
+            if is_blank(*symbol) {
+                problems.push(format!("{} uses a blank which is skipped", name));
+            }
+        }
+
+        match operator {
+            OperatorRef::Leading(LeadingOp{kind: LeadingOpKind::Paren, ..}) if symbols.len() < 2 => {
+                problems.push(format!("{} is parentheses without a closing symbol", name));
+            },
+            OperatorRef::Following(op) if op.kind.left_bp() <= 0 => {
+                // Between symbols, min_bp is 0
+                problems.push(format!("{} has left_bp {} and never takes a leading expression", name, op.kind.left_bp()));
+            },
+            _ => (),
+        }
+
+        // 1 [ 2 ) 3 ] would read ) as an operator
+        for symbol in symbols[1..].iter() {
+            if let Some(following) = language.following_operators.iter().find(|op| op.symbols.first() == Some(symbol)) {
+                problems.push(format!("The symbol {} of {} is also the following operator {}", symbol, name, following.name));
+            }
+        }
+    }
+
+    for (i, a) in language.leading_operators.iter().enumerate() {
+        for b in language.leading_operators[i + 1..].iter() {
+            if !a.symbols.is_empty() && a.symbols.first() == b.symbols.first() {
+                problems.push(format!("The leading operators {} and {} start with the same {}", a.name, b.name, a.symbols[0]));
+            }
+        }
+    }
+
+    for (i, a) in language.following_operators.iter().enumerate() {
+        for b in language.following_operators[i + 1..].iter() {
+            if !a.symbols.is_empty() && a.symbols.first() == b.symbols.first() {
+                problems.push(format!("The following operators {} and {} start with the same {}", a.name, b.name, a.symbols[0]));
+            }
+        }
+    }
+
+    // eval and unparse find an operator by the name and the number of children
+    let operators: Vec<&OperatorRef> = operators.iter().filter(|op| !op.symbols().is_empty()).collect();
+    for (i, a) in operators.iter().enumerate() {
+        for b in operators[i + 1..].iter() {
+            if a.name() == b.name() && a.child_bps().len() == b.child_bps().len() {
+                problems.push(format!("Two operators are named {} with {} children", a.name(), a.child_bps().len()));
+            }
+        }
+    }
+
+    problems
+}
@@ -0,0 +1,76 @@
+use async_recursion::*;
+
+use crate::language::*;
+use crate::sexpr::SExpr;
+
+// Graphviz DOT of a parse tree
+//
+// $ pratt ... | dot -Tsvg > tree.svg
+//
+// An operator node is labeled with its name and fixity like "+\ninfix"
+// With binding_powers, each edge is labeled with the min_bp the child was parsed with
+//
+//        +
+//   50 /   \ 51
+//     1     *
+//      80 /   \ 81
+//        2     3
+pub async fn dot(language: &Language, expr: &SExpr, binding_powers: bool) -> String {
+    let mut text = String::from("digraph {\n    ordering=out;\n");
+    let mut count = 0;
+    write_node(language, expr, binding_powers, &mut count, &mut text).await;
+    text.push_str("}\n");
+    text
+}
+
+// Returns the id of the node written
+#[async_recursion]
+async fn write_node(language: &Language, expr: &SExpr, binding_powers: bool, count: &mut usize, text: &mut String) -> String {
+    let id = format!("n{}", count);
+    *count += 1;
+
+    let l = match expr {
+        SExpr::Atom(s) => {
+            text.push_str(&format!("    {} [label={}, shape=plaintext];\n", id, quote(s)));
+            return id;
+        },
+        SExpr::List(l) => l,
+    };
+
+    let (name, children) = match l.split_first() {
+        Some((SExpr::Atom(name), children)) => (name.clone(), children),
+        _ => (String::new(), l.as_slice()),  // Not made by parse_expr but drawn anyway
+    };
+
+    let operator = language.find_operator(&name, children.len());
+    let label = match operator {
+        Some(operator) => format!("{}\n{}", name, operator.fixity()),
+        None => name.clone(),
+    };
+    text.push_str(&format!("    {} [label={}, shape=ellipse];\n", id, quote(&label)));
+
+    let bps = operator.map(|operator| operator.child_bps()).unwrap_or_default();
+    for (i, child) in children.iter().enumerate() {
+        let child_id = write_node(language, child, binding_powers, count, text).await;
+        match bps.get(i) {
+            Some(bp) if binding_powers => text.push_str(&format!("    {} -> {} [label=\"{}\"];\n", id, child_id, bp)),
+            _ => text.push_str(&format!("    {} -> {};\n", id, child_id)),
+        }
+    }
+
+    id
+}
+
+fn quote(s: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
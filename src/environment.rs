@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+
+use crate::eval::Value;
+
+// Variables visible to the evaluator
+// The last scope is the innermost one
+//
+// x = 1             [{x: 1}]
+// { x = { y = 2 } }
+//       ^           [{x: 1}, {}, {y: 2}]
+//   ^               [{x: 2}, {}]  x is found in the outer scope and updated there
+//                 ^ [{x: 2}]
+#[derive(Debug, Clone)]
+pub struct Environment {
+    scopes: Vec<HashMap<String, Value>>,
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Self {
+            scopes: vec![HashMap::new()],  // The global scope is never popped
+        }
+    }
+}
+
+impl Environment {
+    pub async fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name))
+    }
+
+    // Updates the innermost variable of the name, or defines it in the innermost scope
+    pub fn assign(&mut self, name: &str, value: Value) {
+        for scope in self.scopes.iter_mut().rev() {
+            if let Some(variable) = scope.get_mut(name) {
+                *variable = value;
+                return;
+            }
+        }
+
+        self.define(name, value);
+    }
+
+    // Always makes a new variable in the innermost scope, hiding outer ones
+    pub fn define(&mut self, name: &str, value: Value) {
+        self.scopes.last_mut().unwrap().insert(name.into(), value);
+    }
+
+    pub fn push_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    pub fn pop_scope(&mut self) {
+        if self.scopes.len() > 1 {
+            self.scopes.pop();
+        }
+    }
+}
@@ -0,0 +1,37 @@
+// Why parse_expr failed
+// position is the byte index in Input::text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    // The text ended where an atom or a symbol like ) was needed
+    // More text may make it an expression
+    UnexpectedEnd{position: usize, expected: String},
+    UnexpectedChar{position: usize, found: char, expected: String},
+}
+
+impl ParseError {
+    pub fn position(&self) -> usize {
+        match self {
+            ParseError::UnexpectedEnd{position, ..} => *position,
+            ParseError::UnexpectedChar{position, ..} => *position,
+        }
+    }
+
+    pub fn is_incomplete(&self) -> bool {
+        matches!(self, ParseError::UnexpectedEnd{..})
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ParseError::UnexpectedEnd{position, expected} => {
+                write!(f, "Expected {} at {}, got the end", expected, position)
+            },
+            ParseError::UnexpectedChar{position, found, expected} => {
+                write!(f, "Expected {} at {}, got {}", expected, position, found.escape_debug())
+            },
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
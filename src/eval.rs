@@ -0,0 +1,152 @@
+use anyhow::*;
+use async_recursion::*;
+use std::future::Future;
+use std::pin::Pin;
+
+use crate::environment::Environment;
+use crate::language::*;
+use crate::number::*;
+use crate::sexpr::SExpr;
+
+pub type Value = Number;
+
+// The evaluator does not know any operator
+// It looks up the operator making the list in the language and calls its semantics
+//
+// (+ 1 (* 2 3))
+//  ^ find_operator("+", 2) -> Semantics::Function
+// A special form like = gets the children as they are and evaluates them by itself
+pub async fn eval(language: &Language, environment: &mut Environment, expr: &SExpr) -> Result<Value> {
+    eval_in(language, environment, expr, language.domain).await
+}
+
+// The numeric atoms are read in the domain
+// Variables keep the domain they were made in and are promoted when they meet wider numbers
+#[async_recursion]
+pub async fn eval_in(language: &Language, environment: &mut Environment, expr: &SExpr, domain: Domain) -> Result<Value> {
+    match expr {
+        SExpr::Atom(s) if is_identifier(s) => environment.get(s).cloned()
+                .with_context(|| format!("The variable {} is not defined", s)),
+        SExpr::Atom(s) => Number::parse(s, domain),
+        SExpr::List(l) => {
+            let (name, children) = match l.split_first() {
+                Some((SExpr::Atom(name), children)) => (name, children),
+                _ => bail!("Expected an operator name at the head of {}", expr),
+            };
+
+            let operator = language.find_operator(name, children.len())
+                    .with_context(|| format!("Unknown operator {} with {} children", name, children.len()))?;
+            let semantics = operator.semantics()
+                    .with_context(|| format!("The operator {} has no semantics", name))?;
+
+            match semantics {
+                Semantics::Function(f) => {
+                    let mut values = Vec::with_capacity(children.len());
+                    for child in children.iter() {
+                        values.push(eval_in(language, environment, child, domain).await?);
+                    }
+                    f(&values)
+                },
+                Semantics::Special(special) => {
+                    special.eval(name, children, Evaluator{language, environment, domain}).await
+                },
+            }
+        },
+    }
+}
+
+// The semantics of an operator which decides when and whether its children are evaluated,
+// like && not evaluating the right side or = not evaluating the variable
+// A language defines one without changing eval
+//
+// impl SpecialForm for And {
+//     fn eval<'a>(&'a self, name: &'a str, children: &'a [SExpr], mut evaluator: Evaluator<'a>) -> Evaluation<'a> {
+//         Box::pin(async move {
+//             match evaluator.eval(&children[0]).await? {
+//                 zero if zero.is_zero() => Ok(zero),
+//                 _ => evaluator.eval(&children[1]).await,
+//             }
+//         })
+//     }
+// }
+pub trait SpecialForm: Send + Sync {
+    fn eval<'a>(&'a self, name: &'a str, children: &'a [SExpr], evaluator: Evaluator<'a>) -> Evaluation<'a>;
+}
+
+pub type Evaluation<'a> = Pin<Box<dyn Future<Output = Result<Value>> + Send + 'a>>;
+
+// What a SpecialForm evaluates its children and changes the variables with
+pub struct Evaluator<'a> {
+    pub language: &'a Language,
+    pub environment: &'a mut Environment,
+    pub domain: Domain,
+}
+
+impl Evaluator<'_> {
+    pub async fn eval(&mut self, expr: &SExpr) -> Result<Value> {
+        eval_in(self.language, self.environment, expr, self.domain).await
+    }
+}
+
+// (= x 1)  x is not evaluated but bound to the value of 1
+pub struct Assign;
+
+impl SpecialForm for Assign {
+    fn eval<'a>(&'a self, name: &'a str, children: &'a [SExpr], mut evaluator: Evaluator<'a>) -> Evaluation<'a> {
+        Box::pin(async move {
+            match children {
+                [SExpr::Atom(variable), value] if is_identifier(variable) => {
+                    // x = y = 3 is (= x (= y 3)) so an assignment returns the value
+                    let value = evaluator.eval(value).await?;
+                    evaluator.environment.assign(variable, value.clone());
+                    Ok(value)
+                },
+                [target, _] => bail!("Cannot assign to {}", target),
+                _ => bail!("The operator {} needs 2 children to assign", name),
+            }
+        })
+    }
+}
+
+// (if-then-else c t e)  only one of t and e is evaluated
+pub struct Conditional;
+
+impl SpecialForm for Conditional {
+    fn eval<'a>(&'a self, name: &'a str, children: &'a [SExpr], mut evaluator: Evaluator<'a>) -> Evaluation<'a> {
+        Box::pin(async move {
+            match children {
+                [condition, then_expr, else_expr] => {
+                    if !evaluator.eval(condition).await?.is_zero() {
+                        evaluator.eval(then_expr).await
+                    } else {
+                        evaluator.eval(else_expr).await
+                    }
+                },
+                _ => bail!("The operator {} needs 3 children to branch", name),
+            }
+        })
+    }
+}
+
+// (block 1)  1 is evaluated in a new scope of the environment
+pub struct Scope;
+
+impl SpecialForm for Scope {
+    fn eval<'a>(&'a self, name: &'a str, children: &'a [SExpr], mut evaluator: Evaluator<'a>) -> Evaluation<'a> {
+        Box::pin(async move {
+            match children {
+                [body] => {
+                    evaluator.environment.push_scope();
+                    let value = evaluator.eval(body).await;
+                    evaluator.environment.pop_scope();  // Even if the body fails
+                    value
+                },
+                _ => bail!("The operator {} needs 1 child to make a scope", name),
+            }
+        })
+    }
+}
+
+pub fn is_identifier(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_lowercase())
+}
@@ -0,0 +1,235 @@
+use async_recursion::*;
+
+use crate::language::*;
+use crate::sexpr::SExpr;
+
+// Random languages and expressions for property tests
+// The same seed makes the same ones, so a failure can be reproduced by its seed
+
+// xorshift64*, which is enough for tests and needs no crate
+#[derive(Debug, Clone)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub async fn new(seed: u64) -> Self {
+        Self {
+            state: seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1,  // The state must not be 0
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    // 0..n
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    // low..=high
+    pub fn range(&mut self, low: i32, high: i32) -> i32 {
+        low + self.below((high - low + 1) as usize) as i32
+    }
+
+    pub fn chance(&mut self, percent: usize) -> bool {
+        self.below(100) < percent
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+// The symbols of random operators
+// ( ) is kept for the grouping operator, digits and lowercase letters are atoms
+// and . would be read as a part of a number
+const SYMBOLS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ!#$%&*+,-/:<=>?@[]^_{|}~;'\"`\\";
+
+// A language which check_language finds no problem in
+//
+// Every symbol is used once except that a prefix operator may start with
+// the first symbol of a following operator like - of pratt6
+// The grouping operator paren is always there, so any tree can be written as a text
+// Binding powers are small so that operators often tie
+pub async fn random_language(random: &mut Random) -> Language {
+    let mut symbols: Vec<char> = SYMBOLS.chars().collect();
+    for i in (1..symbols.len()).rev() {  // Fisher-Yates
+        symbols.swap(i, random.below(i + 1));
+    }
+    let mut take = |count: usize| -> Vec<char> { symbols.drain(..count).collect() };
+
+    let mut following_operators = Vec::new();
+    for i in 0..random.range(1, 4) {
+        let count = if random.chance(20) { 2 } else { 1 };
+        following_operators.push(infix(format!("infix{}", i), take(count), random.range(1, 10), random.range(1, 10)).await);
+    }
+    for i in 0..random.range(0, 2) {
+        let count = if random.chance(30) { 2 } else { 1 };
+        following_operators.push(postfix(format!("postfix{}", i), take(count), random.range(1, 10)).await);
+    }
+
+    let mut leading_operators = vec![paren("paren".into(), vec!['(', ')']).await.with_law(Law::Transparent)];
+    for i in 0..random.range(0, 2) {
+        let mut prefix_symbols = take(if random.chance(20) { 3 } else { 1 });
+        if random.chance(30) {
+            let shared = random.choose(&following_operators).symbols[0];
+            if leading_operators.iter().all(|op| op.symbols[0] != shared) {
+                prefix_symbols[0] = shared;
+            }
+        }
+        leading_operators.push(prefix(format!("prefix{}", i), prefix_symbols, random.range(0, 10)).await);
+    }
+    if random.chance(50) {
+        let count = random.range(2, 3) as usize;
+        leading_operators.push(paren("bracket".into(), take(count)).await);
+    }
+
+    Language::new(leading_operators, following_operators).await
+}
+
+// A tree of the operators of the language and atoms like 7, 42 or x
+#[async_recursion]
+pub async fn random_tree(language: &Language, random: &mut Random, depth: usize) -> SExpr {
+    if depth == 0 || random.chance(25) {
+        return match random.below(3) {
+            0 => SExpr::Atom(random.below(100).to_string()),
+            1 => SExpr::Atom(random.below(10).to_string()),
+            _ => SExpr::Atom(((b'a' + random.below(26) as u8) as char).to_string()),
+        };
+    }
+
+    let operators: Vec<OperatorRef> = language.leading_operators.iter().map(OperatorRef::Leading)
+            .chain(language.following_operators.iter().map(OperatorRef::Following))
+            .collect();
+    let operator = *random.choose(&operators);
+
+    let mut children = vec![SExpr::Atom(operator.name().into())];
+    for _ in 0..operator.child_bps().len() {
+        children.push(random_tree(language, random, depth - 1).await);
+    }
+    SExpr::List(children)
+}
+
+// A text and the tree parse_expr should make of it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Generated {
+    pub text: String,
+    pub expected: SExpr,
+}
+
+const BLANKS: [&str; 4] = ["", "", " ", "\t"];
+
+// A random tree written with the grouping operator paren only where the binding powers need it
+// and sometimes where they do not, so expected has the paren nodes the text has
+// Blanks are put between the tokens at random
+//
+// The language must be made by random_language
+pub async fn random_expression(language: &Language, random: &mut Random, depth: usize) -> Generated {
+    let tree = random_tree(language, random, depth).await;
+    let mut tokens = Vec::new();
+    let expected = write(language, random, &tree, 0, None, &mut tokens).await;
+
+    let mut text = String::new();
+    for token in tokens.iter() {
+        if !text.is_empty() {
+            text.push_str(BLANKS[random.below(BLANKS.len())]);
+        }
+        text.push_str(token);
+    }
+
+    Generated{text, expected}
+}
+
+// prev-op       expr        next-op
+//        min_bp      next_left_bp
+//
+// The expr needs parentheses when
+//   it is a following operator which min_bp does not let take the leading expression
+//   its last operand would take the next operator since its right_bp is less than the left_bp
+// This is the rule of the parser written from the other side, so it is a reference for parse_expr
+#[async_recursion]
+async fn write(
+    language: &Language,
+    random: &mut Random,
+    expr: &SExpr,
+    min_bp: i32,
+    next_left_bp: Option<i32>,
+    tokens: &mut Vec<String>,
+) -> SExpr {
+    let l = match expr {
+        SExpr::Atom(s) => {
+            tokens.push(s.clone());
+            return expr.clone();
+        },
+        SExpr::List(l) => l,
+    };
+
+    let name = match &l[0] {
+        SExpr::Atom(name) => name,
+        SExpr::List(_) => unreachable!("random_tree puts a name at the head"),
+    };
+    let operator = language.find_operator(name, l.len() - 1).expect("random_tree uses the operators of the language");
+    let (left_bp, right_bp) = operator.binding_powers();
+
+    let taken = left_bp.is_none_or(|left_bp| min_bp < left_bp);
+    let ends = match (right_bp, next_left_bp) {
+        (Some(right_bp), Some(next_left_bp)) => right_bp >= next_left_bp,
+        _ => true,
+    };
+
+    if taken && ends && !random.chance(5) {
+        return write_operator(language, random, operator, &l[1..], min_bp, next_left_bp, tokens).await;
+    }
+
+    tokens.push("(".into());
+    let inner = write_operator(language, random, operator, &l[1..], 0, None, tokens).await;
+    tokens.push(")".into());
+    SExpr::List(vec![SExpr::Atom("paren".into()), inner])
+}
+
+async fn write_operator(
+    language: &Language,
+    random: &mut Random,
+    operator: OperatorRef<'_>,
+    children: &[SExpr],
+    min_bp: i32,
+    next_left_bp: Option<i32>,
+    tokens: &mut Vec<String>,
+) -> SExpr {
+    let (left_bp, right_bp) = operator.binding_powers();
+    let mut children = children.iter();
+    let mut expected = vec![SExpr::Atom(operator.name().into())];
+
+    if let Some(left_bp) = left_bp {  // The leading expression is parsed at the same level
+        expected.push(write(language, random, children.next().unwrap(), min_bp, Some(left_bp), tokens).await);
+    }
+
+    for (i, symbol) in operator.symbols().iter().enumerate() {
+        if i > 0 {
+            expected.push(write(language, random, children.next().unwrap(), 0, None, tokens).await);
+        }
+        tokens.push(symbol.to_string());
+    }
+
+    if let Some(right_bp) = right_bp {
+        expected.push(write(language, random, children.next().unwrap(), right_bp, next_left_bp, tokens).await);
+    }
+
+    SExpr::List(expected)
+}
+
+// Symbols, atoms and blanks in a random order, which are mostly not expressions
+// For comparing the errors of the backends
+pub async fn random_text(language: &Language, random: &mut Random, length: usize) -> String {
+    let mut tokens: Vec<String> = vec!["1".into(), "23".into(), "x".into(), " ".into()];
+    let symbols = language.leading_operators.iter().flat_map(|op| op.symbols.iter())
+            .chain(language.following_operators.iter().flat_map(|op| op.symbols.iter()));
+    tokens.extend(symbols.map(|c| c.to_string()));
+
+    (0..length).map(|_| random.choose(&tokens).as_str()).collect()
+}
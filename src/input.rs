@@ -0,0 +1,50 @@
+// The text is a String, or a &str which the atoms of a BorrowedSExpr can outlive the Input with
+pub struct Input<T = String> {
+    pub text: T,
+    pub position: usize,  // If you use i32, you won't use this as an index
+}
+
+impl Input {
+    pub async fn new(text: String) -> Self {
+        Self {
+            text,
+            position: 0,
+        }
+    }
+}
+
+impl<'t> Input<&'t str> {
+    pub async fn borrowed(text: &'t str) -> Self {
+        Self {
+            text,
+            position: 0,
+        }
+    }
+}
+
+impl<T: AsRef<str>> Input<T> {
+    pub async fn peek(&self) -> Option<char> {  // Get a character at the current position
+        self.text.as_ref()[self.position..].chars().find(|c| !is_blank(*c))
+    }
+
+    pub async fn peek_adjacent(&self) -> Option<char> {  // Get a character without skipping blanks
+        self.text.as_ref()[self.position..].chars().next()
+    }
+
+    pub fn peek_position(&self) -> usize {  // Where the character of peek is
+        let rest = &self.text.as_ref()[self.position..];
+        self.position + (rest.len() - rest.trim_start_matches(is_blank).len())
+    }
+
+    pub async fn bump(&mut self) {  // Increment the position
+        while self.text.as_ref()[self.position..].starts_with(is_blank) {
+            self.position += 1;
+        }
+        self.position += self.peek().await.unwrap().len_utf8();
+    }
+}
+
+// x = y = 3 is read as x=y=3
+pub fn is_blank(c: char) -> bool {
+    c == ' ' || c == '\t'
+}
@@ -0,0 +1,124 @@
+use crate::error::ParseError;
+use crate::input::Input;
+use crate::language::*;
+use crate::parser::{expect_symbol, parse_atom};
+use crate::sexpr::SExpr;
+
+// A call of parse_level of parse_expr, kept on a Vec instead of the stack
+struct Level<'a> {
+    min_bp: i32,
+    leading_expr: Option<SExpr>,
+    // The operator whose operand the level above is parsing, with the children so far
+    operator: Option<(OperatorRef<'a>, Vec<SExpr>)>,
+}
+
+// What parse_level does next
+enum Step {
+    Leading,       // Parse the leading expression
+    Following,     // One round of the 'main loop
+    Return(SExpr), // Give the expression to the level below
+}
+
+// The same Pratt loop as parse_expr with an explicit stack of levels,
+// so ------1 or ((((1)))) of any depth is parsed in constant stack space
+// It gives the same SExpr and the same ParseError as parse_expr
+pub async fn parse_expr_iterative(language: &Language, input: &mut Input, min_bp: i32) -> Result<SExpr, ParseError> {
+    let mut levels = vec![Level{min_bp, leading_expr: None, operator: None}];
+    let mut step = Step::Leading;
+
+    loop {
+        step = match step {
+            Step::Leading => {
+                let leading_operator = match input.peek().await {
+                    Some(c) => language.leading_operators.iter().find(|op| op.symbols[0] == c),
+                    None => None,
+                };
+
+                match leading_operator {
+                    Some(leading_operator) => {
+                        input.bump().await;
+                        let children = vec![SExpr::Atom(leading_operator.name.clone())];
+                        start(&mut levels, OperatorRef::Leading(leading_operator), children)
+                    },
+                    None => {  // There is not any leading expression matching
+                        let atom = parse_atom(input).await?;
+                        levels.last_mut().unwrap().leading_expr = Some(atom);
+                        Step::Following
+                    },
+                }
+            },
+            Step::Following => {
+                let level = levels.last_mut().unwrap();
+                let following_operator = match input.peek().await {
+                    Some(c) => language.following_operators.iter().find(|op| op.symbols[0] == c),
+                    None => None,
+                };
+
+                match following_operator {
+                    // prev-op       Atom        curr-op
+                    //        min_bp      left_bp
+                    Some(following_operator) if level.min_bp < following_operator.kind.left_bp() => {
+                        input.bump().await;
+                        let leading_expr = level.leading_expr.take().unwrap();
+                        let children = vec![SExpr::Atom(following_operator.name.clone()), leading_expr];
+                        start(&mut levels, OperatorRef::Following(following_operator), children)
+                    },
+                    _ => Step::Return(levels.pop().unwrap().leading_expr.unwrap()),
+                }
+            },
+            Step::Return(expr) => {
+                let level = match levels.last_mut() {
+                    Some(level) => level,
+                    None => return Ok(expr),
+                };
+                let (operator, children) = level.operator.as_mut().unwrap();
+                let operator = *operator;
+                children.push(expr);
+
+                // The operands after the first symbol, like 2 and 3 of I 1 T 2 E 3 -> [1, 2, 3]
+                let operands = match operator {
+                    OperatorRef::Leading(_) => children.len() - 1,
+                    OperatorRef::Following(_) => children.len() - 2,
+                };
+                let symbols = operator.symbols();
+
+                if operands < symbols.len() {  // It got back because of the correct symbol
+                    expect_symbol(input, symbols[operands]).await?;
+                    input.bump().await;
+                    next_operand(&mut levels, operator, operands + 1)
+                } else {
+                    finish(&mut levels)
+                }
+            },
+        };
+    }
+}
+
+// After the first symbol
+fn start<'a>(levels: &mut Vec<Level<'a>>, operator: OperatorRef<'a>, children: Vec<SExpr>) -> Step {
+    levels.last_mut().unwrap().operator = Some((operator, children));
+    next_operand(levels, operator, 1)
+}
+
+// After the symbols up to symbols[symbols_read - 1]
+fn next_operand<'a>(levels: &mut Vec<Level<'a>>, operator: OperatorRef<'a>, symbols_read: usize) -> Step {
+    let min_bp = if symbols_read < operator.symbols().len() {
+        0  // An inner operand ends at the next symbol
+    } else {
+        match operator.binding_powers() {
+            (_, Some(right_bp)) => right_bp,
+            (_, None) => return finish(levels),
+        }
+    };
+
+    levels.push(Level{min_bp, leading_expr: None, operator: None});
+    Step::Leading
+}
+
+// The operator has all the children and becomes the leading expression
+fn finish(levels: &mut [Level]) -> Step {
+    let level = levels.last_mut().unwrap();
+    let (_, children) = level.operator.take().unwrap();
+    level.leading_expr = Some(SExpr::List(children));
+    Step::Following
+}
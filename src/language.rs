@@ -0,0 +1,296 @@
+use anyhow::*;
+
+use crate::eval::{SpecialForm, Value};
+use crate::number::Domain;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "fixity", rename_all = "lowercase"))]
+pub enum LeadingOpKind {
+    Prefix{right_bp: i32},
+    Paren,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "fixity", rename_all = "lowercase"))]
+pub enum FollowingOpKind {
+    Postfix{left_bp: i32},
+    Infix{left_bp: i32, right_bp: i32},
+}
+
+impl LeadingOpKind {
+    pub fn fixity(&self) -> &'static str {
+        match self {
+            LeadingOpKind::Prefix{..} => "prefix",
+            LeadingOpKind::Paren => "paren",
+        }
+    }
+}
+
+impl FollowingOpKind {
+    pub fn fixity(&self) -> &'static str {
+        match self {
+            FollowingOpKind::Postfix{..} => "postfix",
+            FollowingOpKind::Infix{..} => "infix",
+        }
+    }
+
+    pub fn left_bp(&self) -> i32 {
+        match self {
+            FollowingOpKind::Postfix{left_bp} => *left_bp,
+            FollowingOpKind::Infix{left_bp, ..} => *left_bp,
+        }
+    }
+}
+
+// What an operator means
+// The evaluator gives the values of the children in the order of the SExpr
+//         (+ 1 2)           -> [1, 2]
+//         (if-then-else 1 2 3) -> [1, 2, 3]
+pub type Function = Box<dyn Fn(&[Value]) -> Result<Value> + Send + Sync>;
+
+pub enum Semantics {
+    Function(Function),            // The children are evaluated first, and simplify can fold it
+    Special(Box<dyn SpecialForm>), // The children are given as they are, like Assign, Conditional and Scope
+}
+
+impl Semantics {
+    pub fn function<F>(f: F) -> Self
+    where
+        F: Fn(&[Value]) -> Result<Value> + Send + Sync + 'static,
+    {
+        Semantics::Function(Box::new(f))
+    }
+
+    pub fn special<S: SpecialForm + 'static>(special: S) -> Self {
+        Semantics::Special(Box::new(special))
+    }
+}
+
+impl std::fmt::Debug for Semantics {  // Closures are not Debug
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Semantics::Function(_) => write!(f, "Function(..)"),
+            Semantics::Special(_) => write!(f, "Special(..)"),
+        }
+    }
+}
+
+// What the simplifier may rewrite without knowing the semantics
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum Law {
+    Identity(String),       // (+ x 0) -> x, (+ 0 x) -> x
+    RightIdentity(String),  // (- x 0) -> x
+    Involution,             // (- (- x)) -> x
+    Transparent,            // (paren x) -> x
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Operator<K> {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub kind: K,
+    pub name: String,
+    pub symbols: Vec<char>,
+    #[cfg_attr(feature = "serde", serde(skip))]  // Closures cannot be written
+    pub semantics: Option<Semantics>,  // None if the operator is only syntax
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub laws: Vec<Law>,
+}
+
+impl<K> Operator<K> {
+    pub fn with_semantics(mut self, semantics: Semantics) -> Self {
+        self.semantics = Some(semantics);
+        self
+    }
+
+    pub fn with_law(mut self, law: Law) -> Self {
+        self.laws.push(law);
+        self
+    }
+}
+
+pub type LeadingOp = Operator<LeadingOpKind>;
+pub type FollowingOp = Operator<FollowingOpKind>;
+
+impl LeadingOp {
+    // The number of children following the name in the SExpr
+    //   I 1 T 2 E 3 -> (if-then-else 1 2 3)
+    //   ( 1 )       -> (paren 1)
+    pub fn arity(&self) -> usize {
+        match self.kind {
+            LeadingOpKind::Prefix{..} => self.symbols.len(),
+            LeadingOpKind::Paren => self.symbols.len() - 1,
+        }
+    }
+}
+
+impl FollowingOp {
+    //   1 [ 2 ] -> (subscript 1 2)
+    //   1 + 2   -> (+ 1 2)
+    pub fn arity(&self) -> usize {
+        match self.kind {
+            FollowingOpKind::Postfix{..} => self.symbols.len(),
+            FollowingOpKind::Infix{..} => self.symbols.len() + 1,
+        }
+    }
+}
+
+// An operator looked up by the head of an SExpr::List
+#[derive(Debug, Clone, Copy)]
+pub enum OperatorRef<'a> {
+    Leading(&'a LeadingOp),
+    Following(&'a FollowingOp),
+}
+
+impl<'a> OperatorRef<'a> {
+    pub fn name(&self) -> &'a str {
+        match self {
+            OperatorRef::Leading(op) => &op.name,
+            OperatorRef::Following(op) => &op.name,
+        }
+    }
+
+    pub fn symbols(&self) -> &'a [char] {
+        match self {
+            OperatorRef::Leading(op) => &op.symbols,
+            OperatorRef::Following(op) => &op.symbols,
+        }
+    }
+
+    pub fn arity(&self) -> usize {
+        match self {
+            OperatorRef::Leading(op) => op.arity(),
+            OperatorRef::Following(op) => op.arity(),
+        }
+    }
+
+    pub fn fixity(&self) -> &'static str {
+        match self {
+            OperatorRef::Leading(op) => op.kind.fixity(),
+            OperatorRef::Following(op) => op.kind.fixity(),
+        }
+    }
+
+    // (left_bp, right_bp), None where the operator has no operand on the side
+    pub fn binding_powers(&self) -> (Option<i32>, Option<i32>) {
+        match self {
+            OperatorRef::Leading(op) => match op.kind {
+                LeadingOpKind::Prefix{right_bp} => (None, Some(right_bp)),
+                LeadingOpKind::Paren => (None, None),
+            },
+            OperatorRef::Following(op) => match op.kind {
+                FollowingOpKind::Postfix{left_bp} => (Some(left_bp), None),
+                FollowingOpKind::Infix{left_bp, right_bp} => (Some(left_bp), Some(right_bp)),
+            },
+        }
+    }
+
+    // The min_bp each child is parsed with
+    //   (+ 1 2)              -> [50, 51]  1 is left to the left_bp and 2 is parsed with the right_bp
+    //   (if-then-else 1 2 3) -> [0, 0, 41]
+    pub fn child_bps(&self) -> Vec<i32> {
+        let (left_bp, right_bp) = self.binding_powers();
+
+        let mut bps = Vec::new();
+        bps.extend(left_bp);
+        bps.extend(self.symbols()[1..].iter().map(|_| 0));
+        bps.extend(right_bp);
+        bps
+    }
+
+    pub fn laws(&self) -> &'a [Law] {
+        match self {
+            OperatorRef::Leading(op) => &op.laws,
+            OperatorRef::Following(op) => &op.laws,
+        }
+    }
+
+    pub fn semantics(&self) -> Option<&'a Semantics> {
+        match self {
+            OperatorRef::Leading(op) => op.semantics.as_ref(),
+            OperatorRef::Following(op) => op.semantics.as_ref(),
+        }
+    }
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Language {
+    pub leading_operators: Vec<LeadingOp>,
+    pub following_operators: Vec<FollowingOp>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub domain: Domain,  // The numbers of eval unless eval_in chooses another one
+}
+
+impl Language {
+    pub async fn new(leading_operators: Vec<LeadingOp>, following_operators: Vec<FollowingOp>) -> Self {
+        Self {
+            leading_operators,
+            following_operators,
+            domain: Domain::default(),
+        }
+    }
+
+    pub fn with_domain(mut self, domain: Domain) -> Self {
+        self.domain = domain;
+        self
+    }
+
+    // The same name can be used by a leading and a following operator like - and -
+    // so the number of children tells which one made the SExpr
+    pub fn find_operator(&self, name: &str, arity: usize) -> Option<OperatorRef<'_>> {
+        let leading = self.leading_operators.iter()
+                .find(|op| op.name == name && op.arity() == arity)
+                .map(OperatorRef::Leading);
+
+        leading.or_else(|| {
+            self.following_operators.iter()
+                    .find(|op| op.name == name && op.arity() == arity)
+                    .map(OperatorRef::Following)
+        })
+    }
+}
+
+pub async fn prefix(name: String, symbols: Vec<char>, right_bp: i32) -> LeadingOp {
+    LeadingOp {
+        kind: LeadingOpKind::Prefix{right_bp},
+        name,
+        symbols,
+        semantics: None,
+        laws: Vec::new(),
+    }
+}
+
+pub async fn paren(name: String, symbols: Vec<char>) -> LeadingOp {
+    LeadingOp {
+        kind: LeadingOpKind::Paren,
+        name,
+        symbols,
+        semantics: None,
+        laws: Vec::new(),
+    }
+}
+
+pub async fn postfix(name: String, symbols: Vec<char>, left_bp: i32) -> FollowingOp {
+    FollowingOp {
+        kind: FollowingOpKind::Postfix{left_bp},
+        name,
+        symbols,
+        semantics: None,
+        laws: Vec::new(),
+    }
+}
+
+pub async fn infix(name: String, symbols: Vec<char>, left_bp: i32, right_bp: i32) -> FollowingOp {
+    FollowingOp {
+        kind: FollowingOpKind::Infix{left_bp, right_bp},
+        name,
+        symbols,
+        semantics: None,
+        laws: Vec::new(),
+    }
+}
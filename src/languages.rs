@@ -0,0 +1,79 @@
+use crate::eval::{Assign, Conditional, Scope};
+use crate::language::*;
+
+// The language of src/bin/pratt6.rs with the semantics of the arithmetic
+//
+// -   (
+//  51  0
+//   ?
+// 20
+//   +     -     *
+// 50 51 50 51 80 81
+pub async fn pratt6() -> Language {
+    Language::new(
+            vec![
+                    prefix("-".into(), vec!['-'], 51).await
+                            .with_semantics(Semantics::function(|v| {
+                                v[0].neg()
+                            }))
+                            .with_law(Law::Involution),
+                    prefix("if-then-else".into(), vec!['I', 'T', 'E'], 41).await
+                            .with_semantics(Semantics::special(Conditional)),
+                    paren("paren".into(), vec!['(', ')']).await
+                            .with_semantics(Semantics::function(|v| Ok(v[0].clone())))
+                            .with_law(Law::Transparent),
+            ],
+            vec![
+                    postfix("?".into(), vec!['?'], 20).await,
+                    postfix("subscript".into(), vec!['[', ']'], 100).await,
+                    infix("+".into(), vec!['+'], 50, 51).await
+                            .with_semantics(Semantics::function(|v| {
+                                v[0].add(&v[1])
+                            }))
+                            .with_law(Law::Identity("0".into())),
+                    infix("-".into(), vec!['-'], 50, 51).await
+                            .with_semantics(Semantics::function(|v| {
+                                v[0].sub(&v[1])
+                            }))
+                            .with_law(Law::RightIdentity("0".into())),
+                    infix("*".into(), vec!['*'], 80, 81).await
+                            .with_semantics(Semantics::function(|v| {
+                                v[0].mul(&v[1])
+                            }))
+                            .with_law(Law::Identity("1".into())),
+                    infix("=".into(), vec!['='], 21, 20).await
+                            .with_semantics(Semantics::special(Assign)),
+            ],
+    ).await
+}
+
+// pratt6 with blocks having their own variables and the division
+//
+// { y = 2 } * y  fails because y is gone with the block
+// 1 / 3          is 0, or 1/3 with --domain rational
+pub async fn script() -> Language {
+    let mut language = pratt6().await;
+    language.leading_operators.push(
+            paren("block".into(), vec!['{', '}']).await
+                    .with_semantics(Semantics::special(Scope)),
+    );
+    language.following_operators.push(
+            infix("/".into(), vec!['/'], 80, 81).await
+                    .with_semantics(Semantics::function(|v| {
+                        v[0].div(&v[1])
+                    }))
+                    .with_law(Law::RightIdentity("1".into())),
+    );
+    language
+}
+
+// For --language of the pratt command
+pub const NAMES: [&str; 2] = ["pratt6", "script"];
+
+pub async fn by_name(name: &str) -> Option<Language> {
+    match name {
+        "pratt6" => Some(pratt6().await),
+        "script" => Some(script().await),
+        _ => None,
+    }
+}
@@ -0,0 +1,59 @@
+// The Pratt parser of pratt6 as a library
+// The binaries in src/bin are kept as the step-by-step tutorial
+
+mod annotate;
+mod arena;
+mod backend;
+mod borrowed;
+mod check;
+mod dot;
+mod environment;
+mod error;
+mod eval;
+mod generate;
+mod input;
+mod iterative;
+mod language;
+pub mod languages;
+mod minimize;
+mod number;
+mod observer;
+mod parser;
+mod pretty;
+mod program;
+mod reference;
+mod sexpr;
+mod shunting_yard;
+mod simplify;
+mod stream;
+mod table;
+mod trace;
+mod unparse;
+
+pub use annotate::*;
+pub use arena::*;
+pub use backend::*;
+pub use borrowed::*;
+pub use check::*;
+pub use dot::*;
+pub use environment::*;
+pub use error::*;
+pub use eval::*;
+pub use generate::*;
+pub use input::*;
+pub use iterative::*;
+pub use language::*;
+pub use minimize::*;
+pub use number::*;
+pub use observer::*;
+pub use parser::*;
+pub use pretty::*;
+pub use program::*;
+pub use reference::*;
+pub use sexpr::*;
+pub use shunting_yard::*;
+pub use simplify::*;
+pub use stream::*;
+pub use table::*;
+pub use trace::*;
+pub use unparse::*;
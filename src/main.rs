@@ -1,11 +1,403 @@
-use anyhow::*;
-use async_recursion::async_recursion;
+use anyhow::{anyhow, bail, Context, Error, Result};
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use tokio::io::AsyncReadExt;
 
+use pratt::*;
+
+const USAGE: &str = "\
+Usage: pratt <command> [options] [--] [expression]
+
+Commands:
+  parse             Print the S-expression
+  eval              Print the value, variables are kept from line to line
+  trace             Print why each operator was taken and the binding powers
+  fmt               Print the infix text with only the needed parentheses
+  check-language    Check the language and print its precedence table
+  repl              Read expressions interactively, an incomplete one continues on the next line
+
+Options:
+  --language <name>       pratt6 or script (default: script)
+  --language-file <path>  Read the language from JSON (needs the serde feature)
+  --domain <domain>       integer, big-integer, rational or float
+  --backend <backend>     pratt, shunting-yard or iterative, the same tree but trace is of pratt
+                          (default: pratt)
+                          The latter two parse a deeply nested input, which only parse can print
+                          eval, fmt, trace, --simplify and --dot take a tree up to 1000 deep,
+                          and a flat input like 1+1+...+1 is as deep as it has operators
+  --file <path>           Read expressions from the file, one per line
+  --simplify              parse, fmt: fold constants and remove identities
+  --parenthesize          fmt: put every operator in parentheses
+  --width <n>             parse: break lines to fit the width
+  --dot                   parse: print Graphviz DOT
+  --eval                  repl: also print the value
+
+Without an expression or --file, expressions are read from the standard input.
+
+Exit status:
+  0   OK
+  1   Syntax error
+  2   Evaluation error
+  3   The language has problems
+  64  Usage error
+  65  The tree is too deep for the command
+  74  I/O error";
+
+const SYNTAX_ERROR: u8 = 1;
+const EVAL_ERROR: u8 = 2;
+const LANGUAGE_ERROR: u8 = 3;
+const USAGE_ERROR: u8 = 64;
+const TOO_DEEP: u8 = 65;
+const IO_ERROR: u8 = 74;
+
+#[derive(Debug, Default)]
+struct Options {
+    command: String,
+    language: Option<String>,
+    language_file: Option<String>,
+    domain: Option<Domain>,
+    backend: Backend,
+    file: Option<String>,
+    expression: Option<String>,
+    simplify: bool,
+    parenthesize: bool,
+    width: Option<usize>,
+    dot: bool,
+    eval: bool,
+}
+
+// $ pratt parse 1+2*3
+// (+ 1 (* 2 3))
+// $ pratt fmt --parenthesize 1+2*3
+// (1+(2*3))
+// $ printf 'x = y = 3\nx * 2\n' | pratt eval
+// 3
+// 6
 #[tokio::main]
-async fn main() -> Result<()> {
-    Ok(())
+async fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1).collect()).await {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return ExitCode::from(USAGE_ERROR);
+        },
+    };
+
+    let language = match load_language(&options).await {
+        Ok(language) => language,
+        Err((code, e)) => {
+            eprintln!("{:#}", e);
+            return ExitCode::from(code);
+        },
+    };
+
+    match options.command.as_str() {
+        "check-language" => return check(&language).await,
+        "repl" => return repl(&options, &language).await,
+        _ => (),
+    }
+
+    let source = match read_source(&options).await {
+        Ok(source) => source,
+        Err(e) => {
+            eprintln!("{:#}", e);
+            return ExitCode::from(IO_ERROR);
+        },
+    };
+
+    let mut environment = Environment::new().await;
+    let mut status = None;
+
+    // A blank line of a file is skipped, but pratt parse -- "" is an empty expression
+    let lines: Vec<&str> = match options.expression {
+        Some(_) if source.is_empty() => vec![""],
+        _ => source.lines().collect(),
+    };
+
+    for (i, line) in lines.into_iter().enumerate() {
+        if line.trim().is_empty() && options.expression.is_none() {
+            continue;
+        }
+
+        if let Err((code, e)) = run(&options, &language, &mut environment, line).await {
+            match options.expression {
+                Some(_) => eprintln!("{}", e),
+                None => eprintln!("line {}: {}", i + 1, e),
+            }
+            status = status.or(Some(code));  // The first error tells the status
+        }
+    }
+
+    ExitCode::from(status.unwrap_or(0))
 }
 
-#[async_recursion]
-async fn recursive() {
+async fn parse_args(args: Vec<String>) -> Result<Options> {
+    let mut options = Options::default();
+    let mut args = args.into_iter();
+
+    options.command = args.next().context("No command")?;
+    if !["parse", "eval", "trace", "fmt", "check-language", "repl"].contains(&options.command.as_str()) {
+        bail!("Unknown command {}", options.command);
+    }
+
+    let mut only_expression = false;  // After --, even -1 is the expression
+
+    while let Some(arg) = args.next() {
+        if arg == "--" && !only_expression {
+            only_expression = true;
+            continue;
+        }
+        let mut value = |name: &str| args.next().with_context(|| format!("{} needs a value", name));
+
+        match arg.as_str() {
+            _ if only_expression || !arg.starts_with("--") => match options.expression {
+                None => options.expression = Some(arg),
+                Some(_) => bail!("Unexpected argument {}", arg),
+            },
+            "--language" => options.language = Some(value("--language")?),
+            "--language-file" => options.language_file = Some(value("--language-file")?),
+            "--domain" => options.domain = Some(value("--domain")?.parse()?),
+            "--backend" => options.backend = value("--backend")?.parse()?,
+            "--file" => options.file = Some(value("--file")?),
+            "--simplify" => options.simplify = true,
+            "--parenthesize" => options.parenthesize = true,
+            "--width" => options.width = Some(value("--width")?.parse().context("--width needs a number")?),
+            "--dot" => options.dot = true,
+            "--eval" => options.eval = true,
+            _ => bail!("Unknown option {}", arg),
+        }
+    }
+
+    if options.expression.is_some() && options.file.is_some() {
+        bail!("Give either an expression or --file");
+    }
+    if options.command == "repl" && (options.expression.is_some() || options.file.is_some()) {
+        bail!("repl reads only from the terminal");
+    }
+    if options.language.is_some() && options.language_file.is_some() {
+        bail!("Give either --language or --language-file");
+    }
+
+    Ok(options)
+}
+
+async fn load_language(options: &Options) -> Result<Language, (u8, Error)> {
+    let language = match &options.language_file {
+        Some(path) => {
+            let language = read_language(path).await?;
+            // The parser expects what check_language checks, like the symbols of each operator
+            // check-language itself prints the problems
+            if options.command != "check-language" {
+                let problems = check_language(&language).await;
+                if !problems.is_empty() {
+                    let message = format!("{} has problems, see pratt check-language\n{}", path, problems.join("\n"));
+                    return Err((LANGUAGE_ERROR, anyhow!(message)));
+                }
+            }
+            language
+        },
+        None => {
+            let name = options.language.as_deref().unwrap_or("script");
+            languages::by_name(name).await.ok_or_else(|| {
+                (USAGE_ERROR, anyhow!("Unknown language {}, use one of {}", name, languages::NAMES.join(", ")))
+            })?
+        },
+    };
+
+    Ok(match options.domain {
+        Some(domain) => language.with_domain(domain),
+        None => language,
+    })
+}
+
+#[cfg(feature = "serde")]
+async fn read_language(path: &str) -> Result<Language, (u8, Error)> {
+    let json = tokio::fs::read_to_string(path).await
+            .with_context(|| format!("Cannot read {}", path))
+            .map_err(|e| (IO_ERROR, e))?;
+    serde_json::from_str(&json)
+            .with_context(|| format!("{} is not a language", path))
+            .map_err(|e| (LANGUAGE_ERROR, e))
+}
+
+#[cfg(not(feature = "serde"))]
+async fn read_language(_path: &str) -> Result<Language, (u8, Error)> {
+    Err((USAGE_ERROR, anyhow!("--language-file needs the serde feature")))
+}
+
+async fn read_source(options: &Options) -> Result<String> {
+    if let Some(expression) = &options.expression {
+        return Ok(expression.clone());
+    }
+
+    match &options.file {
+        Some(path) => tokio::fs::read_to_string(path).await.with_context(|| format!("Cannot read {}", path)),
+        None => {
+            let mut source = String::new();
+            tokio::io::stdin().read_to_string(&mut source).await.context("Cannot read the standard input")?;
+            Ok(source)
+        },
+    }
+}
+
+async fn check(language: &Language) -> ExitCode {
+    let problems = check_language(language).await;
+    print!("{}", precedence_text(language).await);
+
+    for problem in problems.iter() {
+        eprintln!("{}", problem);
+    }
+
+    if problems.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(LANGUAGE_ERROR)
+    }
+}
+
+// > (1 +
+// . 2) * 3
+// (* (paren (+ 1 2)) 3)
+//
+// The lines of an expression are joined by a space since a newline is not a blank for Input
+// Ctrl-C drops the lines so far and Ctrl-D quits
+async fn repl(options: &Options, language: &Language) -> ExitCode {
+    let mut editor = match DefaultEditor::new() {
+        Ok(editor) => editor,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(IO_ERROR);
+        },
+    };
+
+    let history = std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".pratt_history"));
+    if let Some(path) = &history {
+        let _ = editor.load_history(path);  // There is none at the first time
+    }
+
+    let mut environment = Environment::new().await;
+    let mut text = String::new();
+
+    loop {
+        let prompt = if text.is_empty() { "> " } else { ". " };
+        let line = match tokio::task::block_in_place(|| editor.readline(prompt)) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => {
+                text.clear();
+                continue;
+            },
+            Err(ReadlineError::Eof) => break,
+            Err(e) => {
+                eprintln!("{}", e);
+                return ExitCode::from(IO_ERROR);
+            },
+        };
+
+        if line.trim().is_empty() {
+            continue;
+        }
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        text.push_str(&line);
+
+        let mut input = Input::new(text.clone()).await;
+        let result = parse_all_with(language, &mut input, options.backend).await;
+        if let Err(e) = &result {
+            if e.is_incomplete() {  // An unclosed paren or a trailing operator
+                continue;
+            }
+        }
+
+        let _ = editor.add_history_entry(text.as_str());
+        text.clear();
+
+        let mut expr = match result {
+            Ok(expr) => expr,
+            Err(e) => {
+                eprintln!("{}", e);
+                continue;
+            },
+        };
+        if options.simplify || options.eval {
+            if let Err((_, e)) = check_depth(&expr) {
+                eprintln!("{}", e);
+                continue;
+            }
+        }
+        if options.simplify {
+            expr = simplify(language, &expr).await;
+        }
+        println!("{}", expr);
+
+        if options.eval {
+            match eval(language, &mut environment, &expr).await {
+                Ok(value) => println!("= {}", value),
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+    }
+
+    if let Some(path) = &history {
+        if let Err(e) = editor.save_history(path) {
+            eprintln!("Cannot save the history to {}: {}", path.display(), e);
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+// The walks of eval, unparse, simplify, dot and annotate recurse over the tree
+// A tree deeper than this is an error instead of an overflow of the stack
+const MAX_DEPTH: usize = 1000;
+
+fn check_depth(expr: &SExpr) -> Result<(), (u8, Error)> {
+    match expr.depth() {
+        depth if depth > MAX_DEPTH => Err((TOO_DEEP, anyhow!("The tree is {} deep, but the command takes up to {}", depth, MAX_DEPTH))),
+        _ => Ok(()),
+    }
+}
+
+async fn run(options: &Options, language: &Language, environment: &mut Environment, line: &str) -> Result<(), (u8, Error)> {
+    let mut input = Input::new(line.into()).await;
+
+    if options.command == "trace" {
+        let (expr, trace) = parse_expr_traced(language, &mut input, 0).await;
+        if let Ok(expr) = &expr {
+            check_depth(expr)?;
+        }
+        print!("{}", trace);
+        let expr = expr.map_err(|e| (SYNTAX_ERROR, e.into()))?;
+        expect_end(&input).await.map_err(|e| (SYNTAX_ERROR, e.into()))?;
+        let annotation = annotate(language, &expr).await.map_err(|e| (SYNTAX_ERROR, e))?;
+        println!("{}", annotation);
+        return Ok(());
+    }
+
+    let mut expr = parse_all_with(language, &mut input, options.backend).await.map_err(|e| (SYNTAX_ERROR, e.into()))?;
+    if options.command != "parse" || options.simplify || options.dot {
+        check_depth(&expr)?;
+    }
+    if options.simplify {
+        expr = simplify(language, &expr).await;
+    }
+
+    match options.command.as_str() {
+        "eval" => {
+            let value = eval(language, environment, &expr).await.map_err(|e| (EVAL_ERROR, e))?;
+            println!("{}", value);
+        },
+        "fmt" if options.parenthesize => {
+            println!("{}", unparse_parenthesized(language, &expr).await.map_err(|e| (SYNTAX_ERROR, e))?);
+        },
+        "fmt" => println!("{}", unparse(language, &expr).await.map_err(|e| (SYNTAX_ERROR, e))?),
+        _ if options.dot => print!("{}", dot(language, &expr, true).await),
+        _ => match options.width {
+            Some(width) => println!("{}", pretty(&expr, width).await),
+            None => println!("{}", expr),
+        },
+    }
+
+    Ok(())
 }
@@ -0,0 +1,219 @@
+use std::future::Future;
+
+use crate::error::ParseError;
+use crate::input::{is_blank, Input};
+use crate::language::*;
+use crate::number::Domain;
+use crate::parser::parse_all;
+use crate::sexpr::SExpr;
+
+// What is wrong with the parse of a text, which minimize keeps while it shrinks the input
+pub trait Failure {
+    fn reproduces(&mut self, language: &Language, text: &str) -> impl Future<Output = bool> + Send;
+}
+
+// A closure tells it from the result of parse_all
+//   |result| matches!(result, Err(ParseError::UnexpectedChar{found: ')', ..}))
+// One like |result| result.is_err() is met by any error, so the text shrinks to ""
+impl<F> Failure for F
+where
+    F: FnMut(&Result<SExpr, ParseError>) -> bool + Send,
+{
+    async fn reproduces(&mut self, language: &Language, text: &str) -> bool {
+        let mut input = Input::new(text.into()).await;
+        let result = parse_all(language, &mut input).await;
+        self(&result)
+    }
+}
+
+// The error of the first text, which each shrunk text must give again
+// The position moves as the text shrinks, so the kind, the found character and expected are compared
+// Make one for each minimize
+#[derive(Debug, Default)]
+pub struct SameError {
+    first: Option<ParseError>,
+}
+
+impl SameError {
+    pub async fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Failure for SameError {
+    async fn reproduces(&mut self, language: &Language, text: &str) -> bool {
+        let mut input = Input::new(text.into()).await;
+        let Err(error) = parse_all(language, &mut input).await else {
+            return false;
+        };
+
+        match &self.first {
+            None => {
+                self.first = Some(error);
+                true
+            },
+            Some(first) => match (first, &error) {
+                (ParseError::UnexpectedEnd{expected: a, ..}, ParseError::UnexpectedEnd{expected: b, ..}) => a == b,
+                (
+                    ParseError::UnexpectedChar{found: a, expected: x, ..},
+                    ParseError::UnexpectedChar{found: b, expected: y, ..},
+                ) => a == b && x == y,
+                _ => false,
+            },
+        }
+    }
+}
+
+// A small input which still fails
+// Display writes it as Rust for a regression test
+#[derive(Debug)]
+pub struct Reproduction {
+    pub language: Language,
+    pub text: String,
+}
+
+// Shrinks the text and the language while the failure reproduces, until nothing can be removed
+//   blanks are removed
+//   runs of characters are dropped, from the half of the text down to one character
+//   runs of characters are replaced by the atom 1, which simplifies a sub-expression like (2+3)
+//   operators are removed from the language
+// The semantics are not kept since the parser does not use them
+// None if the input does not fail in the first place
+pub async fn minimize<F: Failure>(language: &Language, text: &str, failure: &mut F) -> Option<Reproduction> {
+    let mut language = syntax(language);
+    if !failure.reproduces(&language, text).await {
+        return None;
+    }
+
+    let mut chars: Vec<char> = text.chars().collect();
+    loop {
+        let mut progress = false;
+
+        let unblank: Vec<char> = chars.iter().copied().filter(|c| !is_blank(*c)).collect();
+        if unblank.len() < chars.len() && reproduces(failure, &language, &unblank).await {
+            chars = unblank;
+            progress = true;
+        }
+
+        let mut size = chars.len() / 2;
+        while size > 0 {
+            let mut start = 0;
+            while start + size <= chars.len() {
+                let candidate = [&chars[..start], &chars[start + size..]].concat();
+                if reproduces(failure, &language, &candidate).await {
+                    chars = candidate;
+                    progress = true;
+                } else {
+                    start += 1;
+                }
+            }
+            size /= 2;
+        }
+
+        for size in (2..=chars.len()).rev() {
+            let mut start = 0;
+            while start + size <= chars.len() {
+                let candidate = [&chars[..start], &['1'], &chars[start + size..]].concat();
+                if reproduces(failure, &language, &candidate).await {
+                    chars = candidate;
+                    progress = true;
+                }
+                start += 1;
+            }
+        }
+
+        let mut i = 0;
+        while i < language.leading_operators.len() + language.following_operators.len() {
+            let mut candidate = syntax(&language);
+            if i < candidate.leading_operators.len() {
+                candidate.leading_operators.remove(i);
+            } else {
+                candidate.following_operators.remove(i - candidate.leading_operators.len());
+            }
+
+            if reproduces(failure, &candidate, &chars).await {
+                language = candidate;
+                progress = true;
+            } else {
+                i += 1;
+            }
+        }
+
+        if !progress {
+            break;
+        }
+    }
+
+    Some(Reproduction{language, text: chars.into_iter().collect()})
+}
+
+async fn reproduces<F: Failure>(failure: &mut F, language: &Language, chars: &[char]) -> bool {
+    let text: String = chars.iter().collect();
+    failure.reproduces(language, &text).await
+}
+
+// A copy of the language without the semantics, which are closures
+fn syntax(language: &Language) -> Language {
+    let leading_operators = language.leading_operators.iter().map(|op| Operator {
+        kind: op.kind,
+        name: op.name.clone(),
+        symbols: op.symbols.clone(),
+        semantics: None,
+        laws: op.laws.clone(),
+    });
+    let following_operators = language.following_operators.iter().map(|op| Operator {
+        kind: op.kind,
+        name: op.name.clone(),
+        symbols: op.symbols.clone(),
+        semantics: None,
+        laws: op.laws.clone(),
+    });
+
+    Language {
+        leading_operators: leading_operators.collect(),
+        following_operators: following_operators.collect(),
+        domain: language.domain,
+    }
+}
+
+// let language = Language::new(vec![
+//     paren("paren".into(), vec!['(', ')']).await.with_law(Law::Transparent),
+// ], vec![
+//     infix("infix0".into(), vec!['+'], 5, 6).await,
+// ]).await;
+// let mut input = Input::new("1+2".into()).await;
+impl std::fmt::Display for Reproduction {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "let language = Language::new(vec![")?;
+        for op in self.language.leading_operators.iter() {
+            let constructor = match op.kind {
+                LeadingOpKind::Prefix{right_bp} => format!("prefix({:?}.into(), vec!{:?}, {})", op.name, op.symbols, right_bp),
+                LeadingOpKind::Paren => format!("paren({:?}.into(), vec!{:?})", op.name, op.symbols),
+            };
+            writeln!(f, "    {}.await{},", constructor, laws(&op.laws))?;
+        }
+        writeln!(f, "], vec![")?;
+        for op in self.language.following_operators.iter() {
+            let constructor = match op.kind {
+                FollowingOpKind::Postfix{left_bp} => format!("postfix({:?}.into(), vec!{:?}, {})", op.name, op.symbols, left_bp),
+                FollowingOpKind::Infix{left_bp, right_bp} => {
+                    format!("infix({:?}.into(), vec!{:?}, {}, {})", op.name, op.symbols, left_bp, right_bp)
+                },
+            };
+            writeln!(f, "    {}.await{},", constructor, laws(&op.laws))?;
+        }
+        match self.language.domain {
+            Domain::Integer => writeln!(f, "]).await;")?,
+            domain => writeln!(f, "]).await.with_domain(Domain::{:?});", domain)?,
+        }
+        write!(f, "let mut input = Input::new({:?}.into()).await;", self.text)
+    }
+}
+
+fn laws(laws: &[Law]) -> String {
+    laws.iter().map(|law| match law {
+        Law::Identity(atom) => format!(".with_law(Law::Identity({:?}.into()))", atom),
+        Law::RightIdentity(atom) => format!(".with_law(Law::RightIdentity({:?}.into()))", atom),
+        law => format!(".with_law(Law::{:?})", law),
+    }).collect()
+}
@@ -0,0 +1,176 @@
+use anyhow::*;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::*;
+
+// How the numeric atoms are read and computed
+// The order is the order of the promotion when two domains meet
+//         1 (Integer) + 1 (Rational) -> 2 (Rational)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum Domain {
+    #[default]
+    Integer,     // i64, overflow is an error
+    BigInteger,  // Never overflows
+    Rational,    // Exact, 0.1 is 1/10
+    Float,       // f64, 0.1 is rounded
+}
+
+impl std::str::FromStr for Domain {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "integer" => Ok(Domain::Integer),
+            "big-integer" => Ok(Domain::BigInteger),
+            "rational" => Ok(Domain::Rational),
+            "float" => Ok(Domain::Float),
+            _ => bail!("Unknown number domain {}", s),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Number {
+    Integer(i64),
+    BigInteger(BigInt),
+    Rational(BigRational),
+    Float(f64),
+}
+
+impl std::fmt::Display for Number {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Number::Integer(n) => write!(f, "{}", n),
+            Number::BigInteger(n) => write!(f, "{}", n),
+            Number::Rational(n) => write!(f, "{}", n),  // 3/2, or 3 when the denominator is 1
+            Number::Float(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+impl Number {
+    // Reads a numeric atom like 42 or 1.25
+    pub fn parse(s: &str, domain: Domain) -> Result<Self> {
+        match domain {
+            Domain::Integer => s.parse().map(Number::Integer)
+                    .with_context(|| format!("{} is not an i64", s)),
+            Domain::BigInteger => s.parse().map(Number::BigInteger)
+                    .with_context(|| format!("{} is not an integer", s)),
+            Domain::Float => s.parse().map(Number::Float)
+                    .with_context(|| format!("{} is not a float", s)),
+            Domain::Rational => {
+                // 3/10 is what a Rational is displayed as
+                if let Some((numerator, denominator)) = s.split_once('/') {
+                    let numerator: BigInt = numerator.parse().with_context(|| format!("{} is not a fraction", s))?;
+                    let denominator: BigInt = denominator.parse().with_context(|| format!("{} is not a fraction", s))?;
+                    if denominator.is_zero() {
+                        bail!("{} has a zero denominator", s);
+                    }
+                    return Ok(Number::Rational(BigRational::new(numerator, denominator)));
+                }
+
+                // 1.25 -> 125 / 10^2
+                let (integer, fraction) = s.split_once('.').unwrap_or((s, ""));
+                let numerator: BigInt = format!("{}{}", integer, fraction).parse()
+                        .with_context(|| format!("{} is not a decimal", s))?;
+                let denominator = BigInt::from(10).pow(fraction.len() as u32);
+                Ok(Number::Rational(BigRational::new(numerator, denominator)))
+            },
+        }
+    }
+
+    pub fn domain(&self) -> Domain {
+        match self {
+            Number::Integer(_) => Domain::Integer,
+            Number::BigInteger(_) => Domain::BigInteger,
+            Number::Rational(_) => Domain::Rational,
+            Number::Float(_) => Domain::Float,
+        }
+    }
+
+    // Only promotes, a Rational is never made an Integer
+    pub fn promote(&self, domain: Domain) -> Result<Self> {
+        let promoted = match (self, domain) {
+            (n, d) if n.domain() == d => n.clone(),
+            (Number::Integer(n), Domain::BigInteger) => Number::BigInteger(BigInt::from(*n)),
+            (Number::Integer(n), Domain::Rational) => Number::Rational(BigRational::from_integer(BigInt::from(*n))),
+            (Number::Integer(n), Domain::Float) => Number::Float(*n as f64),
+            (Number::BigInteger(n), Domain::Rational) => Number::Rational(BigRational::from_integer(n.clone())),
+            (Number::BigInteger(n), Domain::Float) => Number::Float(n.to_f64().context("Too big for a float")?),
+            (Number::Rational(n), Domain::Float) => Number::Float(n.to_f64().context("Too big for a float")?),
+            (n, d) => bail!("Cannot make {} a number of {:?}", n, d),
+        };
+        Ok(promoted)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        match self {
+            Number::Integer(n) => n.is_zero(),
+            Number::BigInteger(n) => n.is_zero(),
+            Number::Rational(n) => n.is_zero(),
+            Number::Float(n) => n.is_zero(),
+        }
+    }
+
+    pub fn neg(&self) -> Result<Self> {
+        match self {
+            Number::Integer(n) => n.checked_neg().map(Number::Integer).context("Overflow in -"),
+            Number::BigInteger(n) => Ok(Number::BigInteger(-n)),
+            Number::Rational(n) => Ok(Number::Rational(-n)),
+            Number::Float(n) => Ok(Number::Float(-n)),
+        }
+    }
+
+    pub fn add(&self, other: &Self) -> Result<Self> {
+        match self.unify(other)? {
+            (Number::Integer(a), Number::Integer(b)) => a.checked_add(b).map(Number::Integer).context("Overflow in +"),
+            (Number::BigInteger(a), Number::BigInteger(b)) => Ok(Number::BigInteger(a + b)),
+            (Number::Rational(a), Number::Rational(b)) => Ok(Number::Rational(a + b)),
+            (Number::Float(a), Number::Float(b)) => Ok(Number::Float(a + b)),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn sub(&self, other: &Self) -> Result<Self> {
+        match self.unify(other)? {
+            (Number::Integer(a), Number::Integer(b)) => a.checked_sub(b).map(Number::Integer).context("Overflow in -"),
+            (Number::BigInteger(a), Number::BigInteger(b)) => Ok(Number::BigInteger(a - b)),
+            (Number::Rational(a), Number::Rational(b)) => Ok(Number::Rational(a - b)),
+            (Number::Float(a), Number::Float(b)) => Ok(Number::Float(a - b)),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn mul(&self, other: &Self) -> Result<Self> {
+        match self.unify(other)? {
+            (Number::Integer(a), Number::Integer(b)) => a.checked_mul(b).map(Number::Integer).context("Overflow in *"),
+            (Number::BigInteger(a), Number::BigInteger(b)) => Ok(Number::BigInteger(a * b)),
+            (Number::Rational(a), Number::Rational(b)) => Ok(Number::Rational(a * b)),
+            (Number::Float(a), Number::Float(b)) => Ok(Number::Float(a * b)),
+            _ => unreachable!(),
+        }
+    }
+
+    // Integers are truncated toward zero, rationals are exact
+    pub fn div(&self, other: &Self) -> Result<Self> {
+        if other.is_zero() && other.domain() != Domain::Float {
+            bail!("Division by zero");
+        }
+
+        match self.unify(other)? {
+            (Number::Integer(a), Number::Integer(b)) => a.checked_div(b).map(Number::Integer).context("Overflow in /"),
+            (Number::BigInteger(a), Number::BigInteger(b)) => Ok(Number::BigInteger(a / b)),
+            (Number::Rational(a), Number::Rational(b)) => Ok(Number::Rational(a / b)),
+            (Number::Float(a), Number::Float(b)) => Ok(Number::Float(a / b)),
+            _ => unreachable!(),
+        }
+    }
+
+    // Both in the wider domain
+    fn unify(&self, other: &Self) -> Result<(Self, Self)> {
+        let domain = self.domain().max(other.domain());
+        Ok((self.promote(domain)?, other.promote(domain)?))
+    }
+}
@@ -0,0 +1,32 @@
+use crate::input::Input;
+use crate::language::*;
+use crate::sexpr::SExpr;
+
+// Events of parse_expr_observed
+// Every method does nothing by default, so implement only what you need
+// input.peek_position() is where the event happened
+pub trait ParseObserver {
+    // A level of parse_expr starts with min_bp
+    // operator is the one whose operand is parsed, or None at the top level
+    fn enter(&mut self, _input: &Input, _min_bp: i32, _operator: Option<OperatorRef>) {}
+
+    // The level returns its leading expression
+    fn leave(&mut self, _input: &Input, _min_bp: i32, _expr: &SExpr) {}
+
+    fn leading_operator(&mut self, _input: &Input, _operator: &LeadingOp) {}
+
+    // Called before min_bp is compared with the left_bp
+    // The operator takes the leading expression only if min_bp < left_bp
+    fn following_operator(&mut self, _input: &Input, _operator: &FollowingOp, _min_bp: i32, _leading: &SExpr) {}
+
+    // Like ) of ( ), or T and E of I T E
+    fn closing_symbol(&mut self, _input: &Input, _symbol: char) {}
+
+    // Called after the atom is read, so the input is right after it
+    fn atom(&mut self, _input: &Input, _atom: &SExpr) {}
+}
+
+// What parse_expr uses, all calls are optimized away
+pub struct NoObserver;
+
+impl ParseObserver for NoObserver {}
@@ -0,0 +1,203 @@
+use anyhow::Result;
+use async_recursion::*;
+use std::ops::Range;
+
+use crate::error::ParseError;
+use crate::input::Input;
+use crate::language::*;
+use crate::sexpr::SExpr;
+use crate::observer::*;
+use crate::trace::Trace;
+
+pub async fn parse_atom(input: &mut Input) -> Result<SExpr, ParseError> {
+    let span = parse_atom_span(input).await?;
+    Ok(SExpr::Atom(input.text[span].into()))
+}
+
+// Where the atom is in Input::text, for the trees which do not own the atoms
+pub async fn parse_atom_span<T: AsRef<str>>(input: &mut Input<T>) -> Result<Range<usize>, ParseError> {
+    let c = match input.peek().await {
+        Some(c) => c,
+        None => return Err(ParseError::UnexpectedEnd{position: input.text.as_ref().len(), expected: "an atom".into()}),
+    };
+
+    let start = input.peek_position();
+    match c {
+        c if c.is_ascii_digit() => {  // 42 or 1.25
+            input.bump().await;
+            while let Some(c) = input.peek_adjacent().await {
+                if !(c.is_ascii_digit() || c == '.') {
+                    break;
+                }
+                input.bump().await;
+            }
+        },
+        c if c.is_ascii_lowercase() => input.bump().await,  // A variable
+        c => return Err(ParseError::UnexpectedChar{position: start, found: c, expected: "an atom".into()}),
+    }
+    Ok(start..input.position)
+}
+
+// With Binding Power
+// It stops before a character which is not a following operator, like ) of 1)
+pub async fn parse_expr(language: &Language, input: &mut Input, min_bp: i32) -> Result<SExpr, ParseError> {
+    parse_expr_observed(language, input, min_bp, &mut NoObserver).await
+}
+
+// The whole text must be one expression
+pub async fn parse_all(language: &Language, input: &mut Input) -> Result<SExpr, ParseError> {
+    let expr = parse_expr(language, input, 0).await?;
+    expect_end(input).await?;
+    Ok(expr)
+}
+
+pub async fn expect_end<T: AsRef<str>>(input: &Input<T>) -> Result<(), ParseError> {
+    match input.peek().await {
+        None => Ok(()),
+        Some(c) => Err(ParseError::UnexpectedChar{position: input.peek_position(), found: c, expected: "the end".into()}),
+    }
+}
+
+// The same as parse_expr but it also logs why it took or stopped at each operator
+// The trace is kept even if it fails
+pub async fn parse_expr_traced(language: &Language, input: &mut Input, min_bp: i32) -> (Result<SExpr, ParseError>, Trace) {
+    let mut trace = Trace::new(input.text.clone()).await;
+    let expr = parse_expr_observed(language, input, min_bp, &mut trace).await;
+    (expr, trace)
+}
+
+pub async fn parse_expr_observed<O>(language: &Language, input: &mut Input, min_bp: i32, observer: &mut O) -> Result<SExpr, ParseError>
+where
+    O: ParseObserver + Send,
+{
+    observer.enter(input, min_bp, None);
+    parse_level(language, input, min_bp, observer).await
+}
+
+// A recursion for an operand of the operator
+async fn recurse<O>(
+    language: &Language,
+    input: &mut Input,
+    operator: OperatorRef<'_>,
+    min_bp: i32,
+    observer: &mut O,
+) -> Result<SExpr, ParseError>
+where
+    O: ParseObserver + Send,
+{
+    observer.enter(input, min_bp, Some(operator));
+    parse_level(language, input, min_bp, observer).await
+}
+
+#[async_recursion]
+async fn parse_level<O>(language: &Language, input: &mut Input, min_bp: i32, observer: &mut O) -> Result<SExpr, ParseError>
+where
+    O: ParseObserver + Send,
+{
+    let mut leading_expr: SExpr = async {
+        let mut expr = None;
+        let c = match input.peek().await {
+            Some(c) => c,
+            None => return parse_atom(input).await,  // It tells that an expression is needed
+        };
+
+        for leading_operator in language.leading_operators.iter() {  // Operator<LeadingOpKind>
+            if leading_operator.symbols[0] == c {  // Operator<K>.symbols
+                observer.leading_operator(input, leading_operator);
+                input.bump().await;
+                let mut children = vec![SExpr::Atom(leading_operator.name.clone())];
+                let operator = OperatorRef::Leading(leading_operator);
+
+                for symbol in leading_operator.symbols[1..].iter() {
+                    let inner_expr = recurse(language, input, operator, 0, observer).await?;
+                    children.push(inner_expr);
+
+                    // It got back because of the correct symbol
+                    expect_symbol(input, *symbol).await?;
+                    observer.closing_symbol(input, *symbol);
+                    input.bump().await;
+                }
+
+                // If the operator is parentheses, it does not affect the expression following )
+                // This is why there is LeadingOpKind::Paren not having right_bp not needed
+                //
+                // This block looks for the end of the effect
+                // It is needed because, at the end, there is not any symbol to end
+                //         unlike the just before block
+                if let LeadingOpKind::Prefix{right_bp} = leading_operator.kind {
+                    let following_expr = recurse(language, input, operator, right_bp, observer).await?;
+                    children.push(following_expr);
+                }
+
+                expr = Some(SExpr::List(children));
+            }
+        }
+
+        match expr {
+            Some(expr) => Ok(expr),
+            None => {  // There is not any leading expression matching
+                let atom = parse_atom(input).await?;
+                observer.atom(input, &atom);
+                Ok(atom)
+            },
+        }
+    }.await?;
+
+    'main: loop {
+        match input.peek().await {
+            None => break 'main,
+            Some(c) => {
+                // Operator<FollowingOpKind>
+                for following_operator in language.following_operators.iter() {
+                    if following_operator.symbols[0] == c {
+                        observer.following_operator(input, following_operator, min_bp, &leading_expr);
+
+                        // If the right is not greater than the left, it ends
+                        // prev-op       Atom        curr-op
+                        //        min_bp      left_bp
+                        if min_bp >= following_operator.kind.left_bp() {
+                            break 'main;
+                        }
+
+                        input.bump().await;
+                        let mut children
+                                = vec![SExpr::Atom(following_operator.name.clone()), leading_expr];
+                        let operator = OperatorRef::Following(following_operator);
+
+                        for symbol in following_operator.symbols[1..].iter() {
+                            let inner_expr = recurse(language, input, operator, 0, observer).await?;
+                            children.push(inner_expr);
+
+                            expect_symbol(input, *symbol).await?;
+                            observer.closing_symbol(input, *symbol);
+                            input.bump().await;
+                        }
+
+                        // The order is different but this right_bp is still the right_bp
+                        if let FollowingOpKind::Infix{right_bp, ..} = following_operator.kind {
+                            let following_expr = recurse(language, input, operator, right_bp, observer).await?;
+                            children.push(following_expr);
+                        }
+
+                        leading_expr = SExpr::List(children);
+                        continue 'main;
+                    }
+                }
+
+                break 'main;
+            },
+        }
+    }  // 'main: loop
+
+    observer.leave(input, min_bp, &leading_expr);
+    Ok(leading_expr)
+}
+
+pub(crate) async fn expect_symbol<T: AsRef<str>>(input: &Input<T>, symbol: char) -> Result<(), ParseError> {
+    let expected = format!("{}", symbol);
+    match input.peek().await {
+        Some(c) if c == symbol => Ok(()),
+        Some(c) => Err(ParseError::UnexpectedChar{position: input.peek_position(), found: c, expected}),
+        None => Err(ParseError::UnexpectedEnd{position: input.text.as_ref().len(), expected}),
+    }
+}
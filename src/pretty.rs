@@ -0,0 +1,150 @@
+use std::fmt::Write;
+
+use crate::sexpr::SExpr;
+
+// Display with line breaks to fit the width
+// A list is written on one line if it fits, otherwise its children go on their own lines
+//
+// (= x
+//   (if-then-else (paren 3)
+//     (* (+ 1 2) (+ 3 4))
+//     (subscript 5 6)))
+//
+// The head stays next to ( and the other children are indented by 2
+// A single atom longer than the width still overflows
+//
+// Like a group of Wadler's printer, a list breaks only when its flat text does not fit,
+// which is known from the flat widths counted once from the leaves up,
+// so the time is linear and a deep tree does not overflow the stack
+pub async fn pretty(expr: &SExpr, width: usize) -> String {
+    let nodes = Nodes::new(expr);
+    let mut text = String::new();
+    let mut stack = vec![Piece::Expr{index: 0, column: 0, closing: 0}];
+
+    while let Some(piece) = stack.pop() {
+        let (index, column, closing) = match piece {
+            Piece::Expr{index, column, closing} => (index, column, closing),
+            Piece::Line(indent) => {
+                text.push('\n');
+                text.push_str(&" ".repeat(indent));
+                continue;
+            },
+            Piece::Close => {
+                text.push(')');
+                continue;
+            },
+        };
+
+        let expr = nodes.exprs[index];
+        let fits = column + nodes.widths[index] + closing <= width;
+        let l = match expr {
+            SExpr::List(l) if !fits && l.len() > 1 => l,
+            _ => {
+                write!(text, "{}", expr).unwrap();
+                continue;
+            },
+        };
+
+        let children = nodes.children(index);
+        let indent = column + 2;
+        let closing_of = |i: usize| if i == l.len() - 1 { closing + 1 } else { 0 };
+        text.push('(');
+
+        // The pieces are popped in the reverse order
+        stack.push(Piece::Close);
+        for i in (2..l.len()).rev() {
+            stack.push(Piece::Expr{index: children[i], column: indent, closing: closing_of(i)});
+            stack.push(Piece::Line(indent));
+        }
+
+        match &l[0] {
+            // (if-then-else (paren 3)
+            head @ SExpr::Atom(_) => {
+                write!(text, "{} ", head).unwrap();  // Quoted if needed
+                let child_column = column + 1 + nodes.widths[children[0]] + 1;
+                stack.push(Piece::Expr{index: children[1], column: child_column, closing: closing_of(1)});
+            },
+            _ => {
+                stack.push(Piece::Expr{index: children[1], column: indent, closing: closing_of(1)});
+                stack.push(Piece::Line(indent));
+                stack.push(Piece::Expr{index: children[0], column: column + 1, closing: 0});
+            },
+        }
+    }
+
+    text
+}
+
+// column: where the expr starts
+// closing: the number of ) written right after the expr
+enum Piece {
+    Expr{index: usize, column: usize, closing: usize},
+    Line(usize),  // A line break and the indent
+    Close,
+}
+
+// The sub-expressions in pre-order with the width of the flat text and the number of nodes of each
+struct Nodes<'a> {
+    exprs: Vec<&'a SExpr>,
+    widths: Vec<usize>,
+    sizes: Vec<usize>,
+}
+
+impl<'a> Nodes<'a> {
+    fn new(expr: &'a SExpr) -> Self {
+        let mut exprs = Vec::new();
+        let mut stack = vec![expr];
+        while let Some(expr) = stack.pop() {
+            exprs.push(expr);
+            if let SExpr::List(l) = expr {
+                stack.extend(l.iter().rev());
+            }
+        }
+
+        // The children come after their parent, so they are counted first from the end
+        let mut nodes = Nodes{widths: vec![0; exprs.len()], sizes: vec![1; exprs.len()], exprs};
+        for index in (0..nodes.exprs.len()).rev() {
+            match nodes.exprs[index] {
+                atom @ SExpr::Atom(_) => nodes.widths[index] = flat_width(atom),
+                SExpr::List(l) => {
+                    let children = nodes.children(index);
+                    let width: usize = children.iter().map(|child| nodes.widths[*child]).sum();
+                    nodes.widths[index] = 2 + width + l.len().saturating_sub(1);
+                    nodes.sizes[index] = 1 + children.iter().map(|child| nodes.sizes[*child]).sum::<usize>();
+                },
+            }
+        }
+        nodes
+    }
+
+    // The indices of the children, each after the whole sub-tree of the previous one
+    fn children(&self, index: usize) -> Vec<usize> {
+        let count = match self.exprs[index] {
+            SExpr::List(l) => l.len(),
+            SExpr::Atom(_) => 0,
+        };
+        let mut children = Vec::with_capacity(count);
+        let mut child = index + 1;
+        for _ in 0..count {
+            children.push(child);
+            child += self.sizes[child];
+        }
+        children
+    }
+}
+
+// The number of characters Display writes, without making the String
+fn flat_width(expr: &SExpr) -> usize {
+    struct Counter(usize);
+
+    impl Write for Counter {
+        fn write_str(&mut self, s: &str) -> std::fmt::Result {
+            self.0 += s.chars().count();
+            Ok(())
+        }
+    }
+
+    let mut counter = Counter(0);
+    write!(counter, "{}", expr).unwrap();
+    counter.0
+}
@@ -0,0 +1,98 @@
+use crate::error::ParseError;
+use crate::input::Input;
+use crate::language::Language;
+use crate::parser::parse_expr;
+use crate::sexpr::SExpr;
+
+// How statements are put in a text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramRules {
+    // A statement ends before any of them, so they must not be following operators
+    // \n and \r which are not separators are blanks between tokens
+    pub separators: Vec<char>,
+    pub terminator: Terminator,
+}
+
+// Whether the last statement needs a separator after it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Terminator {
+    Optional,  // x = 1; x * 2
+    Required,  // x = 1; x * 2;
+}
+
+impl Default for ProgramRules {
+    fn default() -> Self {
+        Self {
+            separators: vec![';', '\n'],
+            terminator: Terminator::Optional,
+        }
+    }
+}
+
+impl ProgramRules {
+    pub async fn new(separators: Vec<char>, terminator: Terminator) -> Self {
+        Self {
+            separators,
+            terminator,
+        }
+    }
+}
+
+// x = 1;; x * 2
+// (program (= x 1) (* x 2))
+//
+// Separators in a row are empty statements and are skipped
+// An empty text is (program)
+pub async fn parse_program(language: &Language, input: &mut Input, rules: &ProgramRules) -> Result<SExpr, ParseError> {
+    // Input skips only spaces and tabs, and a space for each of \n and \r keeps the positions
+    let newlines: Vec<char> = ['\n', '\r'].into_iter().filter(|c| !rules.separators.contains(c)).collect();
+    let mut program = Input::new(input.text.replace(&newlines[..], " ")).await;
+    program.position = input.position;
+
+    let result = parse_statements(language, &mut program, rules).await;
+    input.position = program.position;
+    result
+}
+
+async fn parse_statements(language: &Language, input: &mut Input, rules: &ProgramRules) -> Result<SExpr, ParseError> {
+    let mut statements = vec![SExpr::Atom("program".into())];
+
+    loop {
+        while let Some(c) = input.peek().await {
+            if !rules.separators.contains(&c) {
+                break;
+            }
+            input.bump().await;
+        }
+
+        if input.peek().await.is_none() {
+            break;
+        }
+
+        statements.push(parse_expr(language, input, 0).await?);
+
+        // parse_expr stopped before a character which is not a following operator
+        match input.peek().await {
+            Some(c) if rules.separators.contains(&c) => continue,
+            Some(c) => return Err(ParseError::UnexpectedChar{
+                position: input.peek_position(),
+                found: c,
+                expected: expected_separator(rules),
+            }),
+            None => match rules.terminator {
+                Terminator::Optional => break,
+                Terminator::Required => return Err(ParseError::UnexpectedEnd{
+                    position: input.text.len(),
+                    expected: expected_separator(rules),
+                }),
+            },
+        }
+    }
+
+    Ok(SExpr::List(statements))
+}
+
+fn expected_separator(rules: &ProgramRules) -> String {
+    let separators: Vec<String> = rules.separators.iter().map(|c| format!("{:?}", c)).collect();
+    format!("a separator {}", separators.join(" or "))
+}
@@ -0,0 +1,173 @@
+use anyhow::*;
+use async_recursion::*;
+
+use crate::input::is_blank;
+use crate::language::*;
+use crate::sexpr::SExpr;
+use crate::unparse::grouping_operator;
+
+// A reference to check parse_expr against, which knows nothing about binding powers
+//
+// Every operator except parentheses is put in the grouping operator,
+// so the text is read back without comparing binding powers
+//
+//   (+ 1 (* 2 3))          -> (1+(2*3))
+//   (* (paren (+ 1 2)) 3)  -> (((1+2))*3)
+//
+// Unlike unparse_parenthesized, a paren node is always written,
+// so parse_parenthesized gives the same tree back
+pub async fn print_parenthesized(language: &Language, expr: &SExpr) -> Result<String> {
+    let grouping = grouping_operator(language).context("The language has no grouping operator")?;
+    let mut text = String::new();
+    print(language, grouping, expr, &mut text).await?;
+    Ok(text)
+}
+
+#[async_recursion]
+async fn print(language: &Language, grouping: &LeadingOp, expr: &SExpr, text: &mut String) -> Result<()> {
+    let l = match expr {
+        SExpr::Atom(s) => {
+            text.push_str(s);
+            return Ok(());
+        },
+        SExpr::List(l) => l,
+    };
+
+    let (name, children) = match l.split_first() {
+        Some((SExpr::Atom(name), children)) => (name, children),
+        _ => bail!("Expected an operator name at the head of {}", expr),
+    };
+    let operator = language.find_operator(name, children.len())
+            .with_context(|| format!("Unknown operator {} with {} children", name, children.len()))?;
+
+    let closed = matches!(operator, OperatorRef::Leading(LeadingOp{kind: LeadingOpKind::Paren, ..}));
+    if !closed {
+        text.push(grouping.symbols[0]);
+    }
+
+    let mut children = children.iter();
+    if let OperatorRef::Following(_) = operator {
+        print(language, grouping, children.next().unwrap(), text).await?;
+    }
+    for (i, symbol) in operator.symbols().iter().enumerate() {
+        if i > 0 {
+            print(language, grouping, children.next().unwrap(), text).await?;
+        }
+        text.push(*symbol);
+    }
+    if let Some(trailing_expr) = children.next() {
+        print(language, grouping, trailing_expr, text).await?;
+    }
+
+    if !closed {
+        text.push(grouping.symbols[1]);
+    }
+    Ok(())
+}
+
+// The inverse of print_parenthesized by recursive descent
+//
+// In the grouping operator there is
+//   a prefix operator with its operands, or
+//   an operand and a following operator with the other operands, or
+//   only an operand, which is the grouping operator itself
+pub async fn parse_parenthesized(language: &Language, text: &str) -> Result<SExpr> {
+    let grouping = grouping_operator(language).context("The language has no grouping operator")?;
+    let mut reader = Reader{chars: text.chars().filter(|c| !is_blank(*c)).collect(), position: 0};
+    let expr = reader.operand(language, grouping).await?;
+    if let Some(c) = reader.peek() {
+        bail!("Expected the end, got {}", c);
+    }
+    Ok(expr)
+}
+
+struct Reader {
+    chars: Vec<char>,  // Without blanks
+    position: usize,
+}
+
+impl Reader {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<()> {
+        match self.peek() {
+            Some(c) if c == symbol => {
+                self.position += 1;
+                Ok(())
+            },
+            Some(c) => bail!("Expected {}, got {}", symbol, c),
+            None => bail!("Expected {}, got the end", symbol),
+        }
+    }
+
+    #[async_recursion]
+    async fn operand(&mut self, language: &Language, grouping: &LeadingOp) -> Result<SExpr> {
+        let c = self.peek().context("Expected an operand, got the end")?;
+
+        if c.is_ascii_digit() {
+            let start = self.position;
+            while self.peek().is_some_and(|c| c.is_ascii_digit() || c == '.') {
+                self.position += 1;
+            }
+            return Ok(SExpr::Atom(self.chars[start..self.position].iter().collect()));
+        }
+        if c.is_ascii_lowercase() {
+            self.position += 1;
+            return Ok(SExpr::Atom(c.into()));
+        }
+
+        if c == grouping.symbols[0] {
+            return self.group(language, grouping).await;
+        }
+
+        let paren = language.leading_operators.iter()
+                .find(|op| op.kind == LeadingOpKind::Paren && op.symbols[0] == c)
+                .with_context(|| format!("Expected an operand, got {}", c))?;
+        let mut children = vec![SExpr::Atom(paren.name.clone())];
+        self.position += 1;
+        for symbol in paren.symbols[1..].iter() {
+            children.push(self.operand(language, grouping).await?);
+            self.expect(*symbol)?;
+        }
+        Ok(SExpr::List(children))
+    }
+
+    async fn group(&mut self, language: &Language, grouping: &LeadingOp) -> Result<SExpr> {
+        self.expect(grouping.symbols[0])?;
+        let c = self.peek().context("Expected an operand, got the end")?;
+
+        let prefix = language.leading_operators.iter()
+                .find(|op| matches!(op.kind, LeadingOpKind::Prefix{..}) && op.symbols[0] == c);
+        let (operator, mut children) = match prefix {
+            Some(prefix) => (OperatorRef::Leading(prefix), vec![SExpr::Atom(prefix.name.clone())]),
+            None => {
+                let leading_expr = self.operand(language, grouping).await?;
+                let c = match self.peek() {
+                    Some(c) if c == grouping.symbols[1] => {  // The grouping operator itself
+                        self.position += 1;
+                        return Ok(SExpr::List(vec![SExpr::Atom(grouping.name.clone()), leading_expr]));
+                    },
+                    Some(c) => c,
+                    None => bail!("Expected a following operator, got the end"),
+                };
+                let following = language.following_operators.iter().find(|op| op.symbols[0] == c)
+                        .with_context(|| format!("Expected a following operator, got {}", c))?;
+                (OperatorRef::Following(following), vec![SExpr::Atom(following.name.clone()), leading_expr])
+            },
+        };
+
+        self.position += 1;
+        for symbol in operator.symbols()[1..].iter() {
+            children.push(self.operand(language, grouping).await?);
+            self.expect(*symbol)?;
+        }
+        if operator.binding_powers().1.is_some() {
+            children.push(self.operand(language, grouping).await?);
+        }
+
+        self.expect(grouping.symbols[1])?;
+        Ok(SExpr::List(children))
+    }
+}
@@ -0,0 +1,231 @@
+use anyhow::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]  // "1" and ["+", "1", "2"]
+pub enum SExpr {
+    Atom(String),
+    List(Vec<SExpr>),
+}
+
+// The children are moved to a stack instead of being dropped recursively,
+// so a deep tree like (- (- (- ... 1))) does not overflow the stack
+impl Drop for SExpr {
+    fn drop(&mut self) {
+        if let SExpr::List(l) = self {
+            drop_children(std::mem::take(l), |expr| match expr {
+                SExpr::List(l) => std::mem::take(l),
+                SExpr::Atom(_) => Vec::new(),
+            });
+        }
+    }
+}
+
+// The canonical text is what Display writes and FromStr reads
+//
+//   (if-then-else 1 2 3)     An atom is written as it is
+//   (f "a b" "(" "\"" "")    unless it has to be quoted
+impl std::fmt::Display for SExpr {  // println!("{}", x);
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write_tree(f, self, SExpr::shape)
+    }
+}
+
+impl SExpr {
+    // The lists on the longest path from the root, counted without recursion
+    // An atom is 0 and (+ 1 (* 2 3)) is 2
+    pub fn depth(&self) -> usize {
+        let mut deepest = 0;
+        let mut stack = vec![(self, 0)];
+        while let Some((expr, depth)) = stack.pop() {
+            deepest = deepest.max(depth);
+            if let SExpr::List(l) = expr {
+                stack.extend(l.iter().map(|child| (child, depth + 1)));
+            }
+        }
+        deepest
+    }
+
+    fn shape(&self) -> Shape<'_, SExpr> {
+        match self {
+            SExpr::Atom(s) => Shape::Atom(s),
+            SExpr::List(l) => Shape::List(None, l),
+        }
+    }
+}
+
+// A node of SExpr, BorrowedSExpr or Arena, which are walked alike without recursion
+pub(crate) enum Shape<'a, N> {
+    Atom(&'a str),
+    List(Option<&'a str>, &'a [N]),  // The name if it is kept apart from the children like in Arena
+}
+
+pub(crate) fn drop_children<T>(mut stack: Vec<T>, take_children: impl Fn(&mut T) -> Vec<T>) {
+    while let Some(mut node) = stack.pop() {
+        stack.append(&mut take_children(&mut node));
+    }
+}
+
+// Display of a tree, which keeps the rest of the lists on a stack instead of recursing
+pub(crate) fn write_tree<'a, N>(
+    f: &mut std::fmt::Formatter,
+    root: &'a N,
+    shape: impl Fn(&'a N) -> Shape<'a, N>,
+) -> std::fmt::Result {
+    let mut stack = vec![Piece::Node(root)];
+    while let Some(piece) = stack.pop() {
+        match piece {
+            Piece::Node(node) => match shape(node) {
+                Shape::Atom(s) => write_atom(f, s)?,
+                Shape::List(name, children) => {
+                    write!(f, "(")?;
+                    stack.push(Piece::Text(")"));
+                    for (i, child) in children.iter().enumerate().rev() {
+                        stack.push(Piece::Node(child));
+                        if i > 0 || name.is_some() {
+                            stack.push(Piece::Text(" "));
+                        }
+                    }
+                    if let Some(name) = name {
+                        write_atom(f, name)?;
+                    }
+                },
+            },
+            Piece::Text(text) => write!(f, "{}", text)?,
+        }
+    }
+    std::fmt::Result::Ok(())
+}
+
+enum Piece<'a, N> {
+    Node(&'a N),
+    Text(&'static str),
+}
+
+// An SExpr owning the atoms, made from the leaves up
+pub(crate) fn to_sexpr<'a, N>(root: &'a N, shape: impl Fn(&'a N) -> Shape<'a, N>) -> SExpr {
+    let mut pending = vec![(root, false)];
+    let mut done: Vec<SExpr> = Vec::new();
+
+    while let Some((node, children_done)) = pending.pop() {
+        match shape(node) {
+            Shape::Atom(s) => done.push(SExpr::Atom(s.into())),
+            Shape::List(_, children) if !children_done => {
+                pending.push((node, true));
+                pending.extend(children.iter().rev().map(|child| (child, false)));
+            },
+            Shape::List(name, children) => {
+                let mut list: Vec<SExpr> = name.map(|name| SExpr::Atom(name.into())).into_iter().collect();
+                list.extend(done.drain(done.len() - children.len()..));
+                done.push(SExpr::List(list));
+            },
+        }
+    }
+
+    done.pop().unwrap()
+}
+
+pub(crate) fn write_atom(f: &mut std::fmt::Formatter, s: &str) -> std::fmt::Result {
+    if !needs_quotes(s) {
+        return write!(f, "{}", s);
+    }
+
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\t' => write!(f, "\\t")?,
+            '\r' => write!(f, "\\r")?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+impl std::str::FromStr for SExpr {  // "(+ 1 2)".parse::<SExpr>()
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut chars = s.chars().peekable();
+        let expr = read(&mut chars)?;
+
+        skip_whitespace(&mut chars);
+        if let Some(c) = chars.next() {
+            bail!("Unexpected {} after {}", c, expr);
+        }
+
+        Ok(expr)
+    }
+}
+
+fn needs_quotes(s: &str) -> bool {
+    s.is_empty() || s.chars().any(|c| c.is_whitespace() || c == '(' || c == ')' || c == '"' || c == '\\')
+}
+
+type Chars<'a> = std::iter::Peekable<std::str::Chars<'a>>;
+
+fn skip_whitespace(chars: &mut Chars) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+// Reads with a stack of the open lists like write_tree, so a deep text does not overflow
+fn read(chars: &mut Chars) -> Result<SExpr> {
+    let mut lists: Vec<Vec<SExpr>> = Vec::new();
+
+    loop {
+        skip_whitespace(chars);
+
+        let expr = match chars.next() {
+            None => bail!("Unexpected end of an S-expression"),
+            Some(')') => match lists.pop() {
+                Some(list) => SExpr::List(list),
+                None => bail!("Unexpected )"),
+            },
+            Some('\\') => bail!("\\ is only allowed in a quoted atom"),
+            Some('(') => {
+                lists.push(Vec::new());
+                continue;
+            },
+            Some('"') => read_quoted(chars)?,
+            Some(c) => read_atom(c, chars)?,
+        };
+
+        match lists.last_mut() {
+            Some(list) => list.push(expr),
+            None => return Ok(expr),
+        }
+    }
+}
+
+fn read_quoted(chars: &mut Chars) -> Result<SExpr> {
+    let mut atom = String::new();
+    loop {
+        match chars.next() {
+            None => bail!("Unexpected end in the quoted atom \"{}", atom),
+            Some('"') => return Ok(SExpr::Atom(atom)),
+            Some('\\') => match chars.next() {
+                Some('"') => atom.push('"'),
+                Some('\\') => atom.push('\\'),
+                Some('n') => atom.push('\n'),
+                Some('t') => atom.push('\t'),
+                Some('r') => atom.push('\r'),
+                Some(c) => bail!("Unknown escape \\{}", c),
+                None => bail!("Unexpected end after \\"),
+            },
+            Some(c) => atom.push(c),
+        }
+    }
+}
+
+fn read_atom(first: char, chars: &mut Chars) -> Result<SExpr> {
+    let mut atom = String::from(first);
+    while let Some(c) = chars.next_if(|c| !(c.is_whitespace() || *c == '(' || *c == ')' || *c == '"')) {
+        if c == '\\' {
+            bail!("\\ is only allowed in a quoted atom");
+        }
+        atom.push(c);
+    }
+    Ok(SExpr::Atom(atom))
+}
@@ -0,0 +1,163 @@
+use std::ops::Range;
+
+use crate::error::ParseError;
+use crate::input::Input;
+use crate::language::*;
+use crate::parser::{expect_symbol, parse_atom_span};
+use crate::sexpr::SExpr;
+
+// An operator on the stack of the shunting-yard
+enum Pending<'a> {
+    // It waits for its last operand, the one its right_bp is for
+    Trailing{operator: OperatorRef<'a>, right_bp: i32},
+    // Between the symbols of a mixfix operator like ( ) or I T E
+    // Nothing pops it since the inside is parsed with min_bp 0
+    Open{operator: OperatorRef<'a>, next_symbol: usize},
+}
+
+// Dijkstra's shunting-yard over the same Language, without recursion
+// It gives the same SExpr and the same ParseError as parse_expr
+//
+// Operands and operators are put on two stacks
+// Before a following operator is taken, the operators whose right_bp is not less than its left_bp are popped,
+// which is where parse_expr returns from the recursion of the right_bp
+//
+// 1 + 2 * 3 - 4
+//   1               operands [1]
+//   +               operators [+ 51]
+//   2               operands [1 2]
+//   * 80 > 51       operators [+ 51, * 81]
+//   3               operands [1 2 3]
+//   - 50 <= 81, 51  pop *, pop +  operands [(+ 1 (* 2 3))]
+pub async fn parse_expr_shunting_yard(language: &Language, input: &mut Input, min_bp: i32) -> Result<SExpr, ParseError> {
+    let mut operands: Vec<SExpr> = Vec::new();
+    shunting_yard(language, input, min_bp, &mut operands).await?;
+    Ok(operands.pop().unwrap())
+}
+
+// Where the shunting-yard puts the operands, so a tree other than SExpr can be made
+// An operator comes after its children like 1 2 3 * +
+pub(crate) trait Operands<'a> {
+    fn atom(&mut self, text: &str, span: Range<usize>);  // At text[span] of Input::text
+    fn operator(&mut self, operator: OperatorRef<'a>, arity: usize);  // Of the last arity operands
+}
+
+impl<'a> Operands<'a> for Vec<SExpr> {
+    fn atom(&mut self, text: &str, span: Range<usize>) {
+        self.push(SExpr::Atom(text[span].into()));
+    }
+
+    fn operator(&mut self, operator: OperatorRef<'a>, arity: usize) {
+        let mut children = vec![SExpr::Atom(operator.name().into())];
+        children.extend(self.drain(self.len() - arity..));
+        self.push(SExpr::List(children));
+    }
+}
+
+pub(crate) async fn shunting_yard<'a, T, O>(language: &'a Language, input: &mut Input<T>, min_bp: i32, operands: &mut O) -> Result<(), ParseError>
+where
+    T: AsRef<str>,
+    O: Operands<'a> + Send,
+{
+    let mut operators: Vec<Pending> = Vec::new();
+    let mut expect_operand = true;
+
+    loop {
+        if expect_operand {
+            let leading_operator = match input.peek().await {
+                Some(c) => language.leading_operators.iter().find(|op| op.symbols[0] == c),
+                None => None,
+            };
+
+            match leading_operator {
+                Some(leading_operator) => {
+                    input.bump().await;
+                    expect_operand = open(OperatorRef::Leading(leading_operator), &mut operators, operands);
+                },
+                None => {
+                    let span = parse_atom_span(input).await?;
+                    operands.atom(input.text.as_ref(), span);
+                    expect_operand = false;
+                },
+            }
+            continue;
+        }
+
+        // After an operand, a following operator or a closing symbol
+        let following_operator = match input.peek().await {
+            Some(c) => language.following_operators.iter().find(|op| op.symbols[0] == c),
+            None => None,
+        };
+        let left_bp = following_operator.map(|op| op.kind.left_bp());
+
+        while let Some(Pending::Trailing{operator, right_bp}) = operators.last() {
+            if left_bp.is_some_and(|left_bp| *right_bp < left_bp) {
+                break;
+            }
+            let operator = *operator;
+            operators.pop();
+            build(operator, operands);
+        }
+
+        let min_bp = match operators.last() {
+            Some(Pending::Trailing{right_bp, ..}) => *right_bp,
+            Some(Pending::Open{..}) => 0,
+            None => min_bp,
+        };
+
+        if let Some(following_operator) = following_operator {
+            if min_bp < following_operator.kind.left_bp() {
+                input.bump().await;
+                expect_operand = open(OperatorRef::Following(following_operator), &mut operators, operands);
+                continue;
+            }
+        }
+
+        // The inside of a mixfix operator ends at its next symbol
+        let (operator, next_symbol) = match operators.last() {
+            Some(Pending::Open{operator, next_symbol}) => (*operator, *next_symbol),
+            _ => break,  // Before a character which is not a following operator, like ) of 1)
+        };
+        let symbol = operator.symbols()[next_symbol];
+        expect_symbol(input, symbol).await?;
+        input.bump().await;
+        operators.pop();
+
+        if next_symbol + 1 < operator.symbols().len() {
+            operators.push(Pending::Open{operator, next_symbol: next_symbol + 1});
+            expect_operand = true;
+        } else {
+            expect_operand = close(operator, &mut operators, operands);
+        }
+    }
+
+    Ok(())
+}
+
+// After the first symbol of an operator, true if an operand comes next
+fn open<'a, O: Operands<'a>>(operator: OperatorRef<'a>, operators: &mut Vec<Pending<'a>>, operands: &mut O) -> bool {
+    if operator.symbols().len() > 1 {
+        operators.push(Pending::Open{operator, next_symbol: 1});
+        return true;
+    }
+    close(operator, operators, operands)
+}
+
+// After the last symbol of an operator, true if an operand comes next
+fn close<'a, O: Operands<'a>>(operator: OperatorRef<'a>, operators: &mut Vec<Pending<'a>>, operands: &mut O) -> bool {
+    match operator.binding_powers() {
+        (_, Some(right_bp)) => {
+            operators.push(Pending::Trailing{operator, right_bp});
+            true
+        },
+        (_, None) => {
+            build(operator, operands);
+            false
+        },
+    }
+}
+
+// The children are the last operands in the order of the text
+fn build<'a, O: Operands<'a>>(operator: OperatorRef<'a>, operands: &mut O) {
+    operands.operator(operator, operator.arity());
+}
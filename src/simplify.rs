@@ -0,0 +1,159 @@
+use async_recursion::*;
+
+use crate::eval::is_identifier;
+use crate::input::Input;
+use crate::language::*;
+use crate::number::*;
+use crate::parser::parse_atom_span;
+use crate::sexpr::SExpr;
+
+// A value is folded only into what the parser reads back, so the result can be unparsed
+//   (+ 1 2)        -> 3
+//   (- 1 6)        -> (- 5)       -5 is not an atom but the prefix operator giving it
+//   (* 1.5 1.5)    (kept)         9/4 of the rational domain is not an atom
+//                                 Nor are NaN and inf of the float domain
+pub async fn simplify(language: &Language, expr: &SExpr) -> SExpr {
+    simplify_in(language, expr, language.domain).await
+}
+
+// The children are simplified first, so one pass is enough
+//
+// (- (paren (- x)))
+//    (paren (- x)) -> (- x)      Law::Transparent
+// (- (- x))        -> x          Law::Involution
+//
+// (* x (+ 1 (* 2 0)))
+//      (+ 1 (* 2 0)) -> 1        Semantics::Function of + and *
+// (* x 1)            -> x        Law::Identity("1")
+//
+// A constant sub-expression failing to evaluate like an overflow is kept as it is
+#[async_recursion]
+pub async fn simplify_in(language: &Language, expr: &SExpr, domain: Domain) -> SExpr {
+    let l = match expr {
+        SExpr::Atom(_) => return expr.clone(),
+        SExpr::List(l) => l,
+    };
+
+    let (name, children) = match l.split_first() {
+        Some((SExpr::Atom(name), children)) => (name, children),
+        _ => return expr.clone(),
+    };
+
+    let mut simple_children = Vec::with_capacity(children.len());
+    for child in children.iter() {
+        simple_children.push(simplify_in(language, child, domain).await);
+    }
+
+    let operator = match language.find_operator(name, children.len()) {
+        Some(operator) => operator,
+        None => {
+            let mut list = vec![SExpr::Atom(name.clone())];
+            list.extend(simple_children);
+            return SExpr::List(list);
+        },
+    };
+
+    for law in operator.laws().iter() {
+        match (law, simple_children.as_slice()) {
+            (Law::Transparent, [x]) => return x.clone(),
+            (Law::Involution, [SExpr::List(inner)]) => {
+                if let [SExpr::Atom(inner_name), x] = inner.as_slice() {
+                    if inner_name == name {
+                        return x.clone();
+                    }
+                }
+            },
+            (Law::Identity(identity), [x, y]) => {
+                if is_number(y, identity, domain) {
+                    return x.clone();
+                }
+                if is_number(x, identity, domain) {
+                    return y.clone();
+                }
+            },
+            (Law::RightIdentity(identity), [x, y]) if is_number(y, identity, domain) => return x.clone(),
+            _ => (),
+        }
+    }
+
+    if let Some(Semantics::Function(f)) = operator.semantics() {
+        let values: Option<Vec<Number>> = simple_children.iter()
+                .map(|child| number(child, domain))
+                .collect();
+        if let Some(Ok(value)) = values.map(|values| f(&values)) {
+            if let Some(folded) = fold(language, &value).await {
+                return folded;
+            }
+        }
+    }
+
+    let mut list = vec![SExpr::Atom(name.clone())];
+    list.extend(simple_children);
+    SExpr::List(list)
+}
+
+// The value as an atom, or a prefix operator whose semantics gives the value from an atom
+async fn fold(language: &Language, value: &Number) -> Option<SExpr> {
+    let text = value.to_string();
+    if reads_back(&text).await {
+        return Some(SExpr::Atom(text));
+    }
+
+    let positive = text.strip_prefix('-')?;
+    if !reads_back(positive).await {
+        return None;
+    }
+    let operand = Number::parse(positive, value.domain()).ok()?;
+    let prefix = language.leading_operators.iter().find(|op| {
+        matches!(op.kind, LeadingOpKind::Prefix{..}) && op.arity() == 1 && match &op.semantics {
+            Some(Semantics::Function(f)) => f(std::slice::from_ref(&operand)).is_ok_and(|result| result == *value),
+            _ => false,
+        }
+    })?;
+    Some(SExpr::List(vec![SExpr::Atom(prefix.name.clone()), SExpr::Atom(positive.into())]))
+}
+
+// parse_atom reads the whole text
+async fn reads_back(text: &str) -> bool {
+    let mut input = Input::new(text.into()).await;
+    parse_atom_span(&mut input).await.is_ok_and(|span| span == (0..text.len()))
+}
+
+fn number(expr: &SExpr, domain: Domain) -> Option<Number> {
+    match expr {
+        SExpr::Atom(s) if !is_identifier(s) => Number::parse(s, domain).ok(),
+        _ => None,
+    }
+}
+
+fn is_number(expr: &SExpr, n: &str, domain: Domain) -> bool {
+    match (number(expr, domain), Number::parse(n, domain)) {
+        (Some(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+// Only Law::Transparent of simplify, so the tree keeps its constants and identities
+// Comparing trees with this ignores the parentheses, which unparse may add or drop
+//
+// (* (paren (+ 1 0)) 2) -> (* (+ 1 0) 2)
+#[async_recursion]
+pub async fn remove_transparent(language: &Language, expr: &SExpr) -> SExpr {
+    let l = match expr {
+        SExpr::Atom(_) => return expr.clone(),
+        SExpr::List(l) => l,
+    };
+
+    if let [SExpr::Atom(name), child] = l.as_slice() {
+        let transparent = language.find_operator(name, 1).is_some_and(|op| op.laws().contains(&Law::Transparent));
+        if transparent {
+            return remove_transparent(language, child).await;
+        }
+    }
+
+    let mut list = Vec::with_capacity(l.len());
+    for child in l.iter() {
+        list.push(remove_transparent(language, child).await);
+    }
+    SExpr::List(list)
+}
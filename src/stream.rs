@@ -0,0 +1,58 @@
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio_stream::wrappers::SplitStream;
+use tokio_stream::{Stream, StreamExt};
+
+use crate::error::ParseError;
+use crate::input::Input;
+use crate::language::Language;
+use crate::parser::parse_all;
+use crate::sexpr::SExpr;
+
+// One expression of a stream
+// number counts the terminated pieces from 1, so it is the line number when the terminator is \n
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parsed {
+    pub number: usize,
+    pub text: String,
+    pub expr: Result<SExpr, ParseError>,
+}
+
+// Expressions separated by newlines from stdin, a socket or a file
+pub fn parse_lines<'a, R>(language: &'a Language, reader: R) -> impl Stream<Item = std::io::Result<Parsed>> + 'a
+where
+    R: AsyncRead + Unpin + Send + 'a,
+{
+    parse_stream(language, reader, b'\n')
+}
+
+// Each piece up to the terminator is one expression and is parsed as soon as it is read
+// A piece which fails is an Err in Parsed::expr and the rest are still parsed
+// Blank pieces are skipped and an Err of the stream is an I/O error or a piece not in UTF-8
+// A piece may span lines, and \n and \r in it are blanks, which keep the positions of the errors
+//
+// let mut exprs = Box::pin(parse_stream(&language, tokio::io::stdin(), b';'));
+// while let Some(parsed) = exprs.next().await { ... }
+pub fn parse_stream<'a, R>(language: &'a Language, reader: R, terminator: u8) -> impl Stream<Item = std::io::Result<Parsed>> + 'a
+where
+    R: AsyncRead + Unpin + Send + 'a,
+{
+    let mut number = 0;
+    let pieces = SplitStream::new(BufReader::new(reader).split(terminator)).map(move |piece| -> std::io::Result<(usize, String)> {
+        number += 1;
+        let mut text = String::from_utf8(piece?)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        if terminator == b'\n' && text.ends_with('\r') {  // CRLF
+            text.pop();
+        }
+        Ok((number, text))
+    });
+
+    pieces
+        .filter(|piece| !matches!(piece, Ok((_, text)) if text.trim().is_empty()))
+        .then(move |piece| async move {
+            let (number, text) = piece?;
+            let mut input = Input::new(text.replace(['\n', '\r'], " ")).await;
+            let expr = parse_all(language, &mut input).await;
+            Ok(Parsed{number, text, expr})
+        })
+}
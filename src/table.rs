@@ -0,0 +1,129 @@
+use crate::language::*;
+
+// The precedence table of a language from the tightest to the loosest
+//
+// | Level | Name | Symbols | Fixity | left_bp | right_bp | Associativity |
+// | --- | --- | --- | --- | --- | --- | --- |
+// | 1 | paren | `(` `)` | paren |  |  |  |
+// | 2 | subscript | `[` `]` | postfix | 100 |  |  |
+// | 3 | * | `*` | infix | 80 | 81 | left |
+//
+// An operator binds as tight as the binding power next to its operand
+//   infix and postfix: left_bp, which the leading expression is compared with
+//   prefix: right_bp
+// Parentheses are the tightest since nothing can take their inside
+pub async fn precedence_markdown(language: &Language) -> String {
+    let mut text = String::new();
+    text.push_str("| Level | Name | Symbols | Fixity | left_bp | right_bp | Associativity |\n");
+    text.push_str("| --- | --- | --- | --- | --- | --- | --- |\n");
+
+    for row in rows(language).iter() {
+        let symbols: Vec<String> = row.symbols.iter().map(|c| code_span(*c)).collect();
+        text.push_str(&format!("| {} | {} | {} | {} | {} | {} | {} |\n",
+                row.level, row.name.replace('|', "\\|"), symbols.join(" "), row.fixity,
+                row.left_bp, row.right_bp, row.associativity));
+    }
+
+    text
+}
+
+// | ends a cell even in a code span unless escaped,
+// and a backtick needs a longer fence with spaces inside
+fn code_span(symbol: char) -> String {
+    match symbol {
+        '|' => "`\\|`".into(),
+        '`' => "`` ` ``".into(),
+        c => format!("`{}`", c),
+    }
+}
+
+// The same table with the columns aligned by spaces
+pub async fn precedence_text(language: &Language) -> String {
+    let mut lines = vec![[
+        "Level".to_string(), "Name".into(), "Symbols".into(), "Fixity".into(),
+        "left_bp".into(), "right_bp".into(), "Associativity".into(),
+    ]];
+
+    for row in rows(language).iter() {
+        let symbols: Vec<String> = row.symbols.iter().map(|c| c.to_string()).collect();
+        lines.push([
+            row.level.to_string(), row.name.clone(), symbols.join(" "), row.fixity.into(),
+            row.left_bp.clone(), row.right_bp.clone(), row.associativity.into(),
+        ]);
+    }
+
+    let mut widths = [0; 7];
+    for line in lines.iter() {
+        for (width, cell) in widths.iter_mut().zip(line.iter()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut text = String::new();
+    for line in lines.iter() {
+        let cells: Vec<String> = line.iter().zip(widths.iter())
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect();
+        text.push_str(cells.join("  ").trim_end());
+        text.push('\n');
+    }
+
+    text
+}
+
+struct Row {
+    level: usize,
+    name: String,
+    symbols: Vec<char>,
+    fixity: &'static str,
+    left_bp: String,
+    right_bp: String,
+    associativity: &'static str,
+}
+
+fn rows(language: &Language) -> Vec<Row> {
+    let operators = language.leading_operators.iter().map(OperatorRef::Leading)
+            .chain(language.following_operators.iter().map(OperatorRef::Following));
+
+    let mut keyed: Vec<(i32, OperatorRef)> = operators.map(|operator| {
+        let key = match operator.binding_powers() {
+            (Some(left_bp), _) => left_bp,
+            (None, Some(right_bp)) => right_bp,
+            (None, None) => i32::MAX,
+        };
+        (key, operator)
+    }).collect();
+    keyed.sort_by_key(|(key, _)| std::cmp::Reverse(*key));  // Stable, so the order of the language is kept in a level
+
+    let mut rows = Vec::new();
+    let mut level = 0;
+    let mut previous_key = None;
+
+    for (key, operator) in keyed.into_iter() {
+        if previous_key != Some(key) {
+            level += 1;
+            previous_key = Some(key);
+        }
+
+        let (left_bp, right_bp) = operator.binding_powers();
+
+        // 1+2+3 with (50, 50) is (+ (+ 1 2) 3) because the 2nd + is not greater than min_bp 50
+        let associativity = match (left_bp, right_bp) {
+            (Some(left_bp), Some(right_bp)) if left_bp > right_bp => "right",
+            (Some(_), Some(_)) => "left",
+            _ => "",
+        };
+
+        rows.push(Row{
+            level,
+            name: operator.name().into(),
+            symbols: operator.symbols().to_vec(),
+            fixity: operator.fixity(),
+            left_bp: left_bp.map(|bp| bp.to_string()).unwrap_or_default(),
+            right_bp: right_bp.map(|bp| bp.to_string()).unwrap_or_default(),
+            associativity,
+        });
+    }
+
+    rows
+}
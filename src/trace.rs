@@ -0,0 +1,151 @@
+use crate::input::Input;
+use crate::language::*;
+use crate::observer::ParseObserver;
+use crate::sexpr::SExpr;
+
+// What parse_expr_traced decided at each step
+// leading is the text of the tree cut at LEADING_LIMIT chars, since a clone of each tree
+// would take memory quadratic in the length of a flat input like 1+1+...+1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    // prev-op       leading        curr-op
+    //        min_bp         left_bp
+    Compare{position: usize, depth: usize, name: String, min_bp: i32, left_bp: i32, leading: String},
+    // The operator parses its operand with min_bp, which is the right_bp or 0 between symbols
+    Recurse{position: usize, depth: usize, name: String, min_bp: i32},
+    Return{position: usize, depth: usize, leading: String},
+}
+
+pub const LEADING_LIMIT: usize = 60;
+
+// Stops the writing at the limit, so a large tree is not written whole
+struct Limited {
+    text: String,
+    chars: usize,
+    cut: bool,
+}
+
+impl std::fmt::Write for Limited {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        for c in s.chars() {
+            if self.chars == LEADING_LIMIT {
+                self.cut = true;
+                return Err(std::fmt::Error);
+            }
+            self.text.push(c);
+            self.chars += 1;
+        }
+        Ok(())
+    }
+}
+
+fn leading_text(expr: &SExpr) -> String {
+    use std::fmt::Write;
+
+    let mut limited = Limited{text: String::new(), chars: 0, cut: false};
+    let _ = write!(limited, "{}", expr);
+    if limited.cut {
+        limited.text.push_str("...");
+    }
+    limited.text
+}
+
+impl Step {
+    pub fn position(&self) -> usize {
+        match self {
+            Step::Compare{position, ..} => *position,
+            Step::Recurse{position, ..} => *position,
+            Step::Return{position, ..} => *position,
+        }
+    }
+}
+
+// 1+2*3
+//  ^     + : min_bp 0 < left_bp 50, take leading: 1
+//   ^    + : recurse with min_bp 51
+//    ^     * : min_bp 51 < left_bp 80, take leading: 2
+//     ^    * : recurse with min_bp 81
+//      ^     return leading: 3
+//      ^   return leading: (* 2 3)
+//      ^ return leading: (+ 1 (* 2 3))
+#[derive(Debug, Clone, Default)]
+pub struct Trace {
+    pub text: String,
+    pub steps: Vec<Step>,
+    levels: usize,  // The levels of parse_expr entered and not left yet
+}
+
+impl Trace {
+    pub async fn new(text: String) -> Self {
+        Self {
+            text,
+            ..Self::default()
+        }
+    }
+
+    fn depth(&self) -> usize {  // The top level is 0
+        self.levels.saturating_sub(1)
+    }
+}
+
+impl ParseObserver for Trace {
+    fn enter(&mut self, input: &Input, min_bp: i32, operator: Option<OperatorRef>) {
+        if let Some(operator) = operator {
+            self.steps.push(Step::Recurse{
+                position: input.peek_position(),
+                depth: self.depth(),
+                name: operator.name().into(),
+                min_bp,
+            });
+        }
+        self.levels += 1;
+    }
+
+    fn leave(&mut self, input: &Input, _min_bp: i32, expr: &SExpr) {
+        self.steps.push(Step::Return{
+            position: input.peek_position(),
+            depth: self.depth(),
+            leading: leading_text(expr),
+        });
+        self.levels -= 1;
+    }
+
+    fn following_operator(&mut self, input: &Input, operator: &FollowingOp, min_bp: i32, leading: &SExpr) {
+        self.steps.push(Step::Compare{
+            position: input.peek_position(),
+            depth: self.depth(),
+            name: operator.name.clone(),
+            min_bp,
+            left_bp: operator.kind.left_bp(),
+            leading: leading_text(leading),
+        });
+    }
+}
+
+impl std::fmt::Display for Trace {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let width = self.text.chars().count() + 1;
+        writeln!(f, "{}", self.text)?;
+
+        for step in self.steps.iter() {
+            let column = self.text[..step.position().min(self.text.len())].chars().count();
+            write!(f, "{}^{}", " ".repeat(column), " ".repeat(width - column))?;
+
+            match step {
+                Step::Compare{depth, name, min_bp, left_bp, leading, ..} => {
+                    let (sign, decision) = if min_bp < left_bp { ("<", "take") } else { (">=", "stop at") };
+                    writeln!(f, "{}{} : min_bp {} {} left_bp {}, {} leading: {}",
+                            "  ".repeat(*depth), name, min_bp, sign, left_bp, decision, leading)?;
+                },
+                Step::Recurse{depth, name, min_bp, ..} => {
+                    writeln!(f, "{}{} : recurse with min_bp {}", "  ".repeat(*depth), name, min_bp)?;
+                },
+                Step::Return{depth, leading, ..} => {
+                    writeln!(f, "{}return leading: {}", "  ".repeat(*depth), leading)?;
+                },
+            }
+        }
+
+        Ok(())
+    }
+}
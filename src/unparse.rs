@@ -0,0 +1,141 @@
+use anyhow::*;
+use async_recursion::*;
+
+use crate::language::*;
+use crate::sexpr::SExpr;
+
+// The inverse of parse_expr
+// Parentheses are put only where the binding powers would group the text differently
+//
+//   (* (paren (+ 1 2)) 3)  -> (1+2)*3   The paren of the tree is kept
+//   (* (+ 1 2) 3)          -> (1+2)*3   The paren of the language is added
+//   (+ 1 (* 2 3))          -> 1+2*3
+//
+// Parsing the text gives the same tree except the added parentheses,
+// which are operators with Law::Transparent and removed by simplify or remove_transparent
+// simplify writes a negative value as (- 3), since -3 is not an atom
+pub async fn unparse(language: &Language, expr: &SExpr) -> Result<String> {
+    let mut text = String::new();
+    write_expr(language, expr, 0, None, Parentheses::Minimal, &mut text).await?;
+    Ok(text)
+}
+
+// Every operator except the parentheses themselves is put in parentheses
+// to show how the language grouped the input
+//
+//   1+2*3  -> (1+(2*3))
+//   -1*2?  -> ((-(1*2))?)
+pub async fn unparse_parenthesized(language: &Language, expr: &SExpr) -> Result<String> {
+    let mut text = String::new();
+    write_expr(language, expr, 0, None, Parentheses::Full, &mut text).await?;
+    Ok(text)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Parentheses {
+    Minimal,
+    Full,
+}
+
+// The grouping operator like ( ) which can be added without changing the meaning
+pub fn grouping_operator(language: &Language) -> Option<&LeadingOp> {
+    language.leading_operators.iter().find(|op| {
+        op.kind == LeadingOpKind::Paren && op.symbols.len() == 2 && op.laws.contains(&Law::Transparent)
+    })
+}
+
+// prev-op       expr        next-op
+//        min_bp      next_left_bp
+//
+// The expr goes wrong when
+//   it starts with a following operator which does not take the leading expression
+//         1   *   (+ 2 3)           the + is not greater than min_bp 81
+//       0   80 81   50
+//   it ends with an operand which takes the next operator
+//         (- 1)   *   2             min_bp 51 of the - is less than 80 of the *
+//         51    80
+#[async_recursion]
+async fn write_expr(
+    language: &Language,
+    expr: &SExpr,
+    min_bp: i32,
+    next_left_bp: Option<i32>,
+    parentheses: Parentheses,
+    text: &mut String,
+) -> Result<()> {
+    let l = match expr {
+        SExpr::Atom(s) => {
+            text.push_str(s);
+            return Ok(());
+        },
+        SExpr::List(l) => l,
+    };
+
+    let (name, children) = match l.split_first() {
+        Some((SExpr::Atom(name), children)) => (name, children),
+        _ => bail!("Expected an operator name at the head of {}", expr),
+    };
+
+    let operator = language.find_operator(name, children.len())
+            .with_context(|| format!("Unknown operator {} with {} children", name, children.len()))?;
+
+    // (1*((2+3))) would be too much
+    if let (Parentheses::Full, [child @ SExpr::List(_)]) = (parentheses, children) {
+        if operator.laws().contains(&Law::Transparent) {
+            return write_expr(language, child, min_bp, next_left_bp, parentheses, text).await;
+        }
+    }
+
+    let (left_bp, right_bp) = operator.binding_powers();
+
+    let starts_wrong = matches!(left_bp, Some(left_bp) if min_bp >= left_bp);
+    let ends_wrong = matches!((right_bp, next_left_bp), (Some(right_bp), Some(next)) if right_bp < next);
+    let always = parentheses == Parentheses::Full && (left_bp, right_bp) != (None, None);
+
+    if starts_wrong || ends_wrong || always {
+        let grouping = grouping_operator(language)
+                .with_context(|| format!("{} needs parentheses but the language has none", expr))?;
+        text.push(grouping.symbols[0]);
+        write_operator(language, operator, children, 0, None, parentheses, text).await?;
+        text.push(grouping.symbols[1]);
+    } else {
+        write_operator(language, operator, children, min_bp, next_left_bp, parentheses, text).await?;
+    }
+
+    Ok(())
+}
+
+async fn write_operator(
+    language: &Language,
+    operator: OperatorRef<'_>,
+    children: &[SExpr],
+    min_bp: i32,
+    next_left_bp: Option<i32>,
+    parentheses: Parentheses,
+    text: &mut String,
+) -> Result<()> {
+    let symbols = operator.symbols();
+    let mut children = children.iter();
+
+    match operator {
+        OperatorRef::Leading(_) => text.push(symbols[0]),
+        OperatorRef::Following(op) => {
+            // The leading expression is parsed before the operator with the same min_bp
+            let leading_expr = children.next().unwrap();
+            write_expr(language, leading_expr, min_bp, Some(op.kind.left_bp()), parentheses, text).await?;
+            text.push(symbols[0]);
+        },
+    }
+
+    // Between the symbols, min_bp is 0 and the next symbol always stops it
+    for symbol in symbols[1..].iter() {
+        write_expr(language, children.next().unwrap(), 0, None, parentheses, text).await?;
+        text.push(*symbol);
+    }
+
+    if let (_, Some(right_bp)) = operator.binding_powers() {
+        write_expr(language, children.next().unwrap(), right_bp, next_left_bp, parentheses, text).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use pratt::*;
+
+// The backends without recursion on inputs deeper than the stack
+// The tree is displayed and dropped without recursion too
+
+const DEPTH: usize = 100_000;
+
+#[tokio::test]
+async fn deep_inputs_are_parsed_without_recursion() {
+    let language = languages::pratt6().await;
+    let cases = [
+        ("-".repeat(DEPTH) + "1", "(- ".repeat(DEPTH) + "1" + &")".repeat(DEPTH)),
+        ("(".repeat(DEPTH) + "1" + &")".repeat(DEPTH), "(paren ".repeat(DEPTH) + "1" + &")".repeat(DEPTH)),
+        ("x=".repeat(DEPTH) + "1", "(= x ".repeat(DEPTH) + "1" + &")".repeat(DEPTH)),
+        ("1+".repeat(DEPTH) + "1", "(+ ".repeat(DEPTH) + "1" + &" 1)".repeat(DEPTH)),
+    ];
+
+    for backend in [Backend::Iterative, Backend::ShuntingYard] {
+        for (text, expected) in cases.iter() {
+            let mut input = Input::new(text.clone()).await;
+            let expr = parse_all_with(&language, &mut input, backend).await.unwrap();
+            assert_eq!(expr.depth(), DEPTH, "{:?} {}...", backend, &text[..10]);
+            assert!(expr.to_string() == *expected, "{:?} {}...", backend, &text[..10]);
+            drop(expr);
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use anyhow::{bail, Result};
+use pratt::*;
+
+// A language outside the crate adds a special form without changing eval
+
+// (&& a b)  b is evaluated only when a is not 0
+struct And;
+
+impl SpecialForm for And {
+    fn eval<'a>(&'a self, name: &'a str, children: &'a [SExpr], mut evaluator: Evaluator<'a>) -> Evaluation<'a> {
+        Box::pin(async move {
+            let [left, right] = children else {
+                bail!("The operator {} needs 2 children", name);
+            };
+            match evaluator.eval(left).await? {
+                zero if zero.is_zero() => Ok(zero),
+                _ => evaluator.eval(right).await,
+            }
+        })
+    }
+}
+
+async fn with_and() -> Language {
+    let mut language = languages::script().await;
+    language.following_operators.push(
+            infix("&&".into(), vec!['&'], 30, 31).await.with_semantics(Semantics::special(And)),
+    );
+    language
+}
+
+async fn eval_text(language: &Language, environment: &mut Environment, text: &str) -> Result<Value> {
+    let expr = parse_all(language, &mut Input::new(text.into()).await).await?;
+    eval(language, environment, &expr).await
+}
+
+#[tokio::test]
+async fn a_special_form_decides_what_is_evaluated() {
+    let language = with_and().await;
+    let mut environment = Environment::new().await;
+
+    // y is not defined, but it is not evaluated either
+    assert_eq!(eval_text(&language, &mut environment, "0&y").await.unwrap(), Number::Integer(0));
+    assert!(eval_text(&language, &mut environment, "1&y").await.is_err());
+    assert_eq!(eval_text(&language, &mut environment, "2&3*4").await.unwrap(), Number::Integer(12));
+
+    // The right side may assign since it gets the environment
+    eval_text(&language, &mut environment, "0&(x=1)").await.unwrap();
+    assert!(environment.get("x").is_none());
+    eval_text(&language, &mut environment, "1&(x=1)").await.unwrap();
+    assert_eq!(environment.get("x"), Some(&Number::Integer(1)));
+}
+
+#[tokio::test]
+async fn the_built_in_special_forms() {
+    let language = languages::script().await;
+    let mut environment = Environment::new().await;
+
+    assert_eq!(eval_text(&language, &mut environment, "x=y=3").await.unwrap(), Number::Integer(3));
+    assert_eq!(environment.get("y"), Some(&Number::Integer(3)));
+    assert!(eval_text(&language, &mut environment, "1=2").await.is_err());
+
+    // Only the branch taken is evaluated
+    assert_eq!(eval_text(&language, &mut environment, "I0Tz=1E2").await.unwrap(), Number::Integer(2));
+    assert!(environment.get("z").is_none());
+
+    // A variable made in a block is gone after it, and one outside is updated
+    assert_eq!(eval_text(&language, &mut environment, "{w=2}*{x=4}").await.unwrap(), Number::Integer(8));
+    assert!(environment.get("w").is_none());
+    assert_eq!(environment.get("x"), Some(&Number::Integer(4)));
+}
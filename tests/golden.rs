@@ -0,0 +1,153 @@
+use pratt::*;
+use std::path::PathBuf;
+
+// Each case is written to tests/golden/<name>.txt as the text and the S-expression or the error
+// Every backend must give the same output
+// After a change of the output, rewrite the files and review the diff
+//
+// BLESS=1 cargo test --test golden
+
+// The expressions of pratt6's main
+const PRATT6: [&str; 9] = [
+    "-1--2",
+    "1+2*3",
+    "1*2+3",
+    "1*(2+3)",
+    "-1+2",
+    "-1*2",
+    "1*2?",
+    "-1*2?",
+    "1=2=I(3)T(4)E(5[6])",
+];
+
+// Each kind of operators of pratt6 with its neighbors and its errors
+const EDGE_CASES: [&str; 40] = [
+    // Atoms and blanks
+    "42",
+    "1.25",
+    "x",
+    " 1 +\t2 ",
+    "",
+    "X",
+    // Prefix
+    "--1",
+    "-x+1",
+    "-1[2]",
+    "-1?",
+    "-",
+    // Paren
+    "(1)",
+    "((1))",
+    "-(1+2)",
+    "(1",
+    "()",
+    "1)",
+    // Mixfix prefix
+    "I1T2E3+4",
+    "I1T2E3=4",
+    "II1T2E3T4E5",
+    "I1T2E",
+    "I1E2",
+    // Postfix
+    "1??",
+    "1?+2",
+    "1+2?",
+    "1=2?",
+    // Mixfix postfix
+    "1[2][3]",
+    "1[2+3]",
+    "1[2",
+    "1[]",
+    // Left associative infix
+    "1+2+3",
+    "1-2-3",
+    "1*2*3",
+    "1+2-3*4",
+    "1+",
+    "+1",
+    // Right associative infix
+    "1=2=3",
+    "x=y=3",
+    "1=2+3=4",
+    "1==2",
+];
+
+#[tokio::test]
+async fn pratt6_examples() {
+    check_golden("pratt6", &languages::pratt6().await, &PRATT6).await;
+}
+
+#[tokio::test]
+async fn edge_cases() {
+    check_golden("edge_cases", &languages::pratt6().await, &EDGE_CASES).await;
+}
+
+// Reading the unparsed text gives the tree back
+// Minimal only puts the parentheses of the tree, and Full adds ones which are removed before comparing
+#[tokio::test]
+async fn unparse_round_trips() {
+    let language = languages::pratt6().await;
+    for case in PRATT6.iter().chain(EDGE_CASES.iter()) {
+        let expr = match parse_all(&language, &mut Input::new(case.to_string()).await).await {
+            Ok(expr) => expr,
+            Err(_) => continue,
+        };
+
+        let minimal = unparse(&language, &expr).await.unwrap();
+        let back = parse_all(&language, &mut Input::new(minimal.clone()).await).await;
+        assert_eq!(back.as_ref(), Ok(&expr), "{:?} is unparsed as {:?}", case, minimal);
+
+        let full = unparse_parenthesized(&language, &expr).await.unwrap();
+        let back = parse_all(&language, &mut Input::new(full.clone()).await).await.unwrap();
+        assert_eq!(
+            remove_transparent(&language, &back).await,
+            remove_transparent(&language, &expr).await,
+            "{:?} is unparsed as {:?}", case, full,
+        );
+    }
+}
+
+async fn check_golden(name: &str, language: &Language, cases: &[&str]) {
+    let mut blocks = Vec::new();
+    for case in cases.iter() {
+        let mut outputs = Vec::new();
+        for backend in [Backend::Pratt, Backend::ShuntingYard, Backend::Iterative] {
+            let mut input = Input::new(case.to_string()).await;
+            outputs.push(match parse_all_with(language, &mut input, backend).await {
+                Ok(expr) => expr.to_string(),
+                Err(e) => format!("error: {}", e),
+            });
+        }
+        for output in outputs[1..].iter() {
+            assert_eq!(&outputs[0], output, "The backends disagree on {:?}", case);
+        }
+        blocks.push(format!("{:?}\n{}\n", case, outputs[0]));
+    }
+    let actual = blocks.join("\n");
+
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden").join(format!("{}.txt", name));
+    if std::env::var_os("BLESS").is_some() {
+        std::fs::write(&path, &actual).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path)
+            .unwrap_or_else(|_| panic!("{} is missing, run BLESS=1 cargo test --test golden", path.display()));
+
+    let expected_blocks: Vec<&str> = expected.split("\n\n").collect();
+    let actual_blocks: Vec<&str> = actual.split("\n\n").collect();
+    let mut differences = Vec::new();
+    for (i, block) in actual_blocks.iter().enumerate() {
+        match expected_blocks.get(i) {
+            Some(expected_block) if expected_block == block => (),
+            Some(expected_block) => differences.push(format!("expected\n{}\ngot\n{}", expected_block, block)),
+            None => differences.push(format!("not in the golden file\n{}", block)),
+        }
+    }
+    if expected_blocks.len() > actual_blocks.len() {
+        differences.push(format!("{} cases are only in the golden file", expected_blocks.len() - actual_blocks.len()));
+    }
+
+    assert!(differences.is_empty(), "{} differs, run BLESS=1 cargo test --test golden if it is intended\n\n{}",
+            path.display(), differences.join("\n\n"));
+}
@@ -0,0 +1,36 @@
+use pratt::*;
+
+// minimize on known failures of pratt6, and the Rust it prints
+
+#[tokio::test]
+async fn the_same_error_is_kept() {
+    let language = languages::pratt6().await;
+    let reproduction = minimize(&language, "1 + (2 * 3]", &mut SameError::new().await).await.unwrap();
+
+    assert_eq!(reproduction.to_string(), [
+        "let language = Language::new(vec![",
+        "    paren(\"paren\".into(), vec!['(', ')']).await.with_law(Law::Transparent),",
+        "], vec![",
+        "]).await;",
+        "let mut input = Input::new(\"(2]\".into()).await;",
+    ].join("\n"));
+
+    // The reproduction gives the error of the input
+    let mut input = Input::new(reproduction.text.clone()).await;
+    assert_eq!(
+        parse_all(&reproduction.language, &mut input).await,
+        Err(ParseError::UnexpectedChar{position: 2, found: ']', expected: ")".into()}),
+    );
+}
+
+#[tokio::test]
+async fn any_error_shrinks_to_nothing() {
+    let language = languages::pratt6().await;
+    let mut failure = |result: &Result<SExpr, ParseError>| result.is_err();
+    let reproduction = minimize(&language, "1 + (2 * 3]", &mut failure).await.unwrap();
+    assert_eq!(reproduction.text, "");
+    assert!(reproduction.language.leading_operators.is_empty() && reproduction.language.following_operators.is_empty());
+
+    // A text which parses is not a failure
+    assert!(minimize(&language, "1+2", &mut SameError::new().await).await.is_none());
+}
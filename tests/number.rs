@@ -0,0 +1,89 @@
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use pratt::*;
+
+fn rational(numerator: i64, denominator: i64) -> Number {
+    Number::Rational(BigRational::new(BigInt::from(numerator), BigInt::from(denominator)))
+}
+
+fn parse(s: &str, domain: Domain) -> Number {
+    Number::parse(s, domain).unwrap()
+}
+
+#[test]
+fn atoms_are_read_in_each_domain() {
+    assert_eq!(parse("42", Domain::Integer), Number::Integer(42));
+    assert!(Number::parse("1.5", Domain::Integer).is_err());
+    assert!(Number::parse("9223372036854775808", Domain::Integer).is_err());
+
+    let big: BigInt = "9223372036854775808".parse().unwrap();
+    assert_eq!(parse("9223372036854775808", Domain::BigInteger), Number::BigInteger(big));
+
+    assert_eq!(parse("0.1", Domain::Rational), rational(1, 10));
+    assert_eq!(parse("1.25", Domain::Rational), rational(5, 4));
+    assert_eq!(parse("3/2", Domain::Rational), rational(3, 2));
+    assert!(Number::parse("3/0", Domain::Rational).is_err());
+    assert!(Number::parse("1/x", Domain::Rational).is_err());
+
+    assert_eq!(parse("0.1", Domain::Float), Number::Float(0.1));
+}
+
+#[test]
+fn i64_overflow_is_an_error() {
+    let max = Number::Integer(i64::MAX);
+    let min = Number::Integer(i64::MIN);
+    assert!(max.add(&Number::Integer(1)).is_err());
+    assert!(min.sub(&Number::Integer(1)).is_err());
+    assert!(max.mul(&Number::Integer(2)).is_err());
+    assert!(min.neg().is_err());
+    assert!(min.div(&Number::Integer(-1)).is_err());
+
+    // The same in BigInteger does not overflow
+    let max = max.promote(Domain::BigInteger).unwrap();
+    assert_eq!(max.add(&Number::Integer(1)).unwrap().to_string(), "9223372036854775808");
+}
+
+#[test]
+fn rationals_are_exact() {
+    let tenth = parse("0.1", Domain::Rational);
+    let sum = tenth.add(&parse("0.2", Domain::Rational)).unwrap();
+    assert_eq!(sum, parse("0.3", Domain::Rational));
+
+    let third = Number::Integer(1).div(&Number::Integer(3).promote(Domain::Rational).unwrap()).unwrap();
+    assert_eq!(third, rational(1, 3));
+    assert_eq!(third.mul(&Number::Integer(3)).unwrap(), rational(1, 1));
+    assert_eq!(third.to_string(), "1/3");
+    assert_eq!(rational(6, 2).to_string(), "3");
+}
+
+#[test]
+fn division() {
+    assert_eq!(Number::Integer(7).div(&Number::Integer(2)).unwrap(), Number::Integer(3));
+    assert_eq!(Number::Integer(-7).div(&Number::Integer(2)).unwrap(), Number::Integer(-3));  // Toward zero
+    assert!(Number::Integer(1).div(&Number::Integer(0)).is_err());
+    assert!(rational(1, 2).div(&rational(0, 1)).is_err());
+    assert_eq!(Number::Float(1.0).div(&Number::Float(0.0)).unwrap(), Number::Float(f64::INFINITY));
+}
+
+#[test]
+fn two_domains_meet_in_the_wider_one() {
+    assert!(Domain::Integer < Domain::BigInteger);
+    assert!(Domain::BigInteger < Domain::Rational);
+    assert!(Domain::Rational < Domain::Float);
+
+    assert_eq!(Number::Integer(1).add(&rational(1, 2)).unwrap(), rational(3, 2));
+    assert_eq!(rational(1, 2).add(&Number::Float(1.0)).unwrap(), Number::Float(1.5));
+    assert_eq!(Number::Integer(2).mul(&Number::Float(0.5)).unwrap(), Number::Float(1.0));
+
+    // Only promotes
+    assert!(rational(1, 2).promote(Domain::Integer).is_err());
+    assert!(Number::Float(1.0).promote(Domain::Rational).is_err());
+}
+
+#[tokio::test]
+async fn script_divides_in_the_domain() {
+    let language = languages::script().await.with_domain(Domain::Rational);
+    let expr = parse_all(&language, &mut Input::new("1/3+1/6".into()).await).await.unwrap();
+    let value = eval(&language, &mut Environment::new().await, &expr).await.unwrap();
+    assert_eq!(value, rational(1, 2));
+}
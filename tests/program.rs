@@ -0,0 +1,58 @@
+use pratt::*;
+
+// parse_program with the default rules and with ; alone
+
+async fn program(text: &str, rules: &ProgramRules) -> Result<String, ParseError> {
+    let language = languages::pratt6().await;
+    let mut input = Input::new(text.into()).await;
+    parse_program(&language, &mut input, rules).await.map(|expr| expr.to_string())
+}
+
+async fn semicolons(terminator: Terminator) -> ProgramRules {
+    ProgramRules::new(vec![';'], terminator).await
+}
+
+#[tokio::test]
+async fn empty_statements_are_skipped() {
+    let rules = ProgramRules::default();
+    assert_eq!(program("", &rules).await.unwrap(), "(program)");
+    assert_eq!(program(";;\n", &rules).await.unwrap(), "(program)");
+    assert_eq!(program(";;x=1;;\n;y;", &rules).await.unwrap(), "(program (= x 1) y)");
+}
+
+#[tokio::test]
+async fn the_last_separator_is_required_or_optional() {
+    let optional = semicolons(Terminator::Optional).await;
+    let required = semicolons(Terminator::Required).await;
+
+    assert_eq!(program("x=1;y", &optional).await.unwrap(), "(program (= x 1) y)");
+    assert_eq!(program("x=1;y;", &optional).await.unwrap(), "(program (= x 1) y)");
+    assert_eq!(program("x=1;y;", &required).await.unwrap(), "(program (= x 1) y)");
+    assert_eq!(
+        program("x=1;y", &required).await,
+        Err(ParseError::UnexpectedEnd{position: 5, expected: "a separator ';'".into()}),
+    );
+}
+
+#[tokio::test]
+async fn crlf_ends_a_line() {
+    let rules = ProgramRules::default();
+    assert_eq!(program("x=1\r\ny=2\r\n", &rules).await.unwrap(), "(program (= x 1) (= y 2))");
+}
+
+#[tokio::test]
+async fn newlines_are_blanks_unless_they_are_separators() {
+    let rules = semicolons(Terminator::Required).await;
+    assert_eq!(program("x=1;\ny=2;\n", &rules).await.unwrap(), "(program (= x 1) (= y 2))");
+    assert_eq!(program("x =\n  1 +\r\n  2;\r\n", &rules).await.unwrap(), "(program (= x (+ 1 2)))");
+
+    // Without a ; the next line continues the statement
+    assert_eq!(
+        program("x=1\ny=2;", &rules).await,
+        Err(ParseError::UnexpectedChar{position: 4, found: 'y', expected: "a separator ';'".into()}),
+    );
+
+    // With \n as a separator, a statement ends at the end of the line
+    let rules = ProgramRules::default();
+    assert!(program("x =\n1", &rules).await.is_err());
+}
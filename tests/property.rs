@@ -0,0 +1,143 @@
+use pratt::*;
+
+// parse_expr against the random expressions and the reference
+// A failure prints the seed, and SEED=<seed> cargo test --test property runs only it
+
+const LANGUAGES: u64 = 200;
+const EXPRESSIONS: usize = 50;
+const DEPTH: usize = 5;
+
+async fn seeds() -> Vec<u64> {
+    match std::env::var("SEED") {
+        Ok(seed) => vec![seed.parse().expect("SEED is a number")],
+        Err(_) => (0..LANGUAGES).collect(),
+    }
+}
+
+#[tokio::test]
+async fn random_languages_have_no_problem() {
+    for seed in seeds().await {
+        let language = random_language(&mut Random::new(seed).await).await;
+        let problems = check_language(&language).await;
+        assert!(problems.is_empty(), "seed {}: {:?}\n{}", seed, problems, precedence_text(&language).await);
+    }
+}
+
+#[tokio::test]
+async fn parse_expr_gives_the_expected_tree() {
+    for seed in seeds().await {
+        let mut random = Random::new(seed).await;
+        let language = random_language(&mut random).await;
+
+        for _ in 0..EXPRESSIONS {
+            let generated = random_expression(&language, &mut random, DEPTH).await;
+            let mut input = Input::new(generated.text.clone()).await;
+            let expr = parse_all(&language, &mut input).await;
+
+            let expected = print_parenthesized(&language, &generated.expected).await.unwrap();
+            let got = match &expr {
+                Ok(expr) => print_parenthesized(&language, expr).await.unwrap(),
+                Err(e) => e.to_string(),
+            };
+            assert_eq!(expected, got, "seed {}: {:?}\n{}", seed, generated.text, precedence_text(&language).await);
+        }
+    }
+}
+
+#[tokio::test]
+async fn parenthesized_text_is_read_back() {
+    for seed in seeds().await {
+        let mut random = Random::new(seed).await;
+        let language = random_language(&mut random).await;
+
+        for _ in 0..EXPRESSIONS {
+            let tree = random_tree(&language, &mut random, DEPTH).await;
+            let text = print_parenthesized(&language, &tree).await.unwrap();
+
+            // Both read the grouping operator as an operator
+            let reference = parse_parenthesized(&language, &text).await.unwrap();
+            assert_eq!(tree, reference, "seed {}: {}", seed, text);
+
+            // parse_all reads the grouping operator of the tree and the added ones alike
+            let mut input = Input::new(text.clone()).await;
+            let expr = parse_all(&language, &mut input).await.unwrap();
+            assert_eq!(
+                remove_transparent(&language, &expr).await,
+                remove_transparent(&language, &tree).await,
+                "seed {}: {}", seed, text,
+            );
+        }
+    }
+}
+
+// The same against unparse and unparse_parenthesized, which decide the parentheses by themselves
+#[tokio::test]
+async fn unparsed_text_is_parsed_back() {
+    for seed in seeds().await {
+        let mut random = Random::new(seed).await;
+        let language = random_language(&mut random).await;
+
+        for _ in 0..EXPRESSIONS {
+            let tree = random_tree(&language, &mut random, DEPTH).await;
+            let expected = remove_transparent(&language, &tree).await;
+
+            for text in [unparse(&language, &tree).await.unwrap(), unparse_parenthesized(&language, &tree).await.unwrap()] {
+                let mut input = Input::new(text.clone()).await;
+                let expr = parse_all(&language, &mut input).await;
+                let got = match &expr {
+                    Ok(expr) => print_parenthesized(&language, &remove_transparent(&language, expr).await).await.unwrap(),
+                    Err(e) => e.to_string(),
+                };
+                let expected = print_parenthesized(&language, &expected).await.unwrap();
+                assert_eq!(expected, got, "seed {}: {:?} of {}\n{}", seed, text, tree, precedence_text(&language).await);
+            }
+        }
+    }
+}
+
+// A text which the backends, the arena or the borrowed tree parse differently
+struct Disagreement;
+
+impl Failure for Disagreement {
+    async fn reproduces(&mut self, language: &Language, text: &str) -> bool {
+        let mut results = Vec::new();
+        for backend in [Backend::Pratt, Backend::ShuntingYard, Backend::Iterative] {
+            let mut input = Input::new(text.into()).await;
+            results.push(parse_all_with(language, &mut input, backend).await);
+        }
+
+        let mut arena = Arena::new().await;
+        let mut input = Input::borrowed(text).await;
+        let root = parse_expr_in_arena(language, &mut input, 0, &mut arena).await;
+        results.push(match root {
+            Ok(root) => expect_end(&input).await.map(|_| arena.to_sexpr(root)),
+            Err(e) => Err(e),
+        });
+
+        let mut input = Input::borrowed(text).await;
+        results.push(parse_all_borrowed(language, &mut input).await.map(BorrowedSExpr::into_owned));
+        results[1..].iter().any(|result| *result != results[0])
+    }
+}
+
+#[tokio::test]
+async fn backends_agree() {
+    for seed in seeds().await {
+        let mut random = Random::new(seed).await;
+        let language = random_language(&mut random).await;
+
+        for i in 0..EXPRESSIONS * 2 {
+            let text = match i % 2 {
+                0 => random_expression(&language, &mut random, DEPTH).await.text,
+                _ => {
+                    let length = random.below(12);
+                    random_text(&language, &mut random, length).await
+                },
+            };
+
+            if let Some(reproduction) = minimize(&language, &text, &mut Disagreement).await {
+                panic!("seed {}: the backends disagree on {:?}\n{}", seed, text, reproduction);
+            }
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use pratt::*;
+
+// The canonical text of SExpr, which Display writes and FromStr reads
+
+fn atom(s: &str) -> SExpr {
+    SExpr::Atom(s.into())
+}
+
+fn list(l: Vec<SExpr>) -> SExpr {
+    SExpr::List(l)
+}
+
+#[test]
+fn display_is_read_back() {
+    let exprs = [
+        atom("1"),
+        atom("x"),
+        atom(""),
+        atom("a b"),
+        atom("("),
+        atom(")"),
+        atom("\""),
+        atom("\\"),
+        atom("\n\t\r"),
+        list(vec![]),
+        list(vec![atom("+"), atom("1"), list(vec![atom("*"), atom("2"), atom("3")])]),
+        list(vec![atom("f"), atom("a b"), atom("("), atom("\""), atom("")]),
+        list(vec![list(vec![list(vec![])])]),
+    ];
+
+    for expr in exprs {
+        let text = expr.to_string();
+        assert_eq!(text.parse::<SExpr>().unwrap(), expr, "{}", text);
+    }
+}
+
+#[test]
+fn quoted_atoms_are_written_only_when_needed() {
+    assert_eq!(list(vec![atom("+"), atom("1"), atom("2")]).to_string(), "(+ 1 2)");
+    assert_eq!(list(vec![atom("f"), atom("a b"), atom("")]).to_string(), r#"(f "a b" "")"#);
+    assert_eq!(atom("\"\\\n").to_string(), r#""\"\\\n""#);
+}
+
+#[test]
+fn blanks_between_tokens_are_skipped() {
+    let expr = " ( + \n 1\t(* 2 3) ) ".parse::<SExpr>().unwrap();
+    assert_eq!(expr, list(vec![atom("+"), atom("1"), list(vec![atom("*"), atom("2"), atom("3")])]));
+    assert_eq!(r#"("a" "")"#.parse::<SExpr>().unwrap(), list(vec![atom("a"), atom("")]));
+}
+
+#[test]
+fn malformed_texts_are_errors() {
+    let texts = [
+        "",
+        "   ",
+        "(",
+        "(+ 1 2",
+        "((1)",
+        ")",
+        "1)",
+        "(1))",
+        "1 2",
+        r#"""#,
+        r#""abc"#,
+        r#""\q""#,
+        r#""\"#,
+        r"a\b",
+        r"\",
+    ];
+
+    for text in texts {
+        assert!(text.parse::<SExpr>().is_err(), "{:?} is read as {:?}", text, text.parse::<SExpr>());
+    }
+}
+
+// Display, FromStr and Drop do not recurse, so a tree deeper than the stack is read back
+// The derived PartialEq recurses, so the trees are compared by the text
+#[test]
+fn a_deep_tree_is_read_back() {
+    const DEPTH: usize = 100_000;
+
+    let mut expr = atom("1");
+    for _ in 0..DEPTH {
+        expr = list(vec![atom("-"), expr]);
+    }
+
+    let text = expr.to_string();
+    assert_eq!(text, format!("{}1{}", "(- ".repeat(DEPTH), ")".repeat(DEPTH)));
+    assert!(text.parse::<SExpr>().unwrap().to_string() == text);
+    assert!(text[1..].parse::<SExpr>().is_err());
+}
@@ -0,0 +1,67 @@
+use pratt::*;
+use tokio_stream::StreamExt;
+
+// parse_lines and parse_stream on a text in memory
+
+// The number and the text of each piece, and the tree or the error
+async fn collect(stream: impl tokio_stream::Stream<Item = std::io::Result<Parsed>>) -> Vec<Result<(usize, String, String), String>> {
+    let items: Vec<_> = stream.collect().await;
+    items.into_iter().map(|item| match item {
+        Ok(Parsed{number, text, expr}) => Ok((number, text, match expr {
+            Ok(expr) => expr.to_string(),
+            Err(e) => format!("error: {}", e.position()),
+        })),
+        Err(e) => Err(e.kind().to_string()),
+    }).collect()
+}
+
+fn parsed(number: usize, text: &str, expr: &str) -> Result<(usize, String, String), String> {
+    Ok((number, text.into(), expr.into()))
+}
+
+#[tokio::test]
+async fn a_bad_line_does_not_stop_the_rest() {
+    let language = languages::pratt6().await;
+    let got = collect(parse_lines(&language, "1+\n2*3\n4\n".as_bytes())).await;
+    assert_eq!(got, [
+        parsed(1, "1+", "error: 2"),
+        parsed(2, "2*3", "(* 2 3)"),
+        parsed(3, "4", "4"),
+    ]);
+}
+
+#[tokio::test]
+async fn blank_lines_are_skipped_but_counted() {
+    let language = languages::pratt6().await;
+    let got = collect(parse_lines(&language, "\n1\n \t\n\n2".as_bytes())).await;
+    assert_eq!(got, [parsed(2, "1", "1"), parsed(5, "2", "2")]);
+}
+
+#[tokio::test]
+async fn crlf_is_a_newline() {
+    let language = languages::pratt6().await;
+    let got = collect(parse_lines(&language, "1+2\r\n\r\n3\r\n".as_bytes())).await;
+    assert_eq!(got, [parsed(1, "1+2", "(+ 1 2)"), parsed(3, "3", "3")]);
+}
+
+#[tokio::test]
+async fn a_custom_terminator() {
+    let language = languages::pratt6().await;
+    let got = collect(parse_stream(&language, "1+\r\n2;;3*4;\n5 6;\n".as_bytes(), b';')).await;
+    assert_eq!(got, [
+        parsed(1, "1+\r\n2", "(+ 1 2)"),
+        parsed(3, "3*4", "(* 3 4)"),
+        parsed(4, "\n5 6", "error: 3"),
+    ]);
+}
+
+#[tokio::test]
+async fn a_piece_not_in_utf8_is_an_error_of_its_own() {
+    let language = languages::pratt6().await;
+    let got = collect(parse_lines(&language, &b"1\n\xff\n2\n"[..])).await;
+    assert_eq!(got, [
+        parsed(1, "1", "1"),
+        Err(std::io::ErrorKind::InvalidData.to_string()),
+        parsed(3, "2", "2"),
+    ]);
+}
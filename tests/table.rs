@@ -0,0 +1,21 @@
+use pratt::*;
+
+// A symbol of Markdown stays in its own cell and its own code span
+
+#[tokio::test]
+async fn symbols_are_escaped_in_markdown() {
+    let language = Language::new(
+        vec![paren("quote".into(), vec!['`', '`']).await],
+        vec![infix("or".into(), vec!['|'], 10, 11).await],
+    ).await;
+
+    let markdown = precedence_markdown(&language).await;
+    let rows: Vec<&str> = markdown.lines().collect();
+    assert_eq!(rows[2], "| 1 | quote | `` ` `` `` ` `` | paren |  |  |  |");
+    assert_eq!(rows[3], r"| 2 | or | `\|` | infix | 10 | 11 | left |");
+
+    // The | of the cells but not the escaped one
+    for row in rows {
+        assert_eq!(row.replace(r"\|", "").matches('|').count(), 8, "{}", row);
+    }
+}